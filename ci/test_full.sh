@@ -27,7 +27,7 @@ if ! check_version $MSRV ; then
   exit 1
 fi
 
-FEATURES=(lossy-conversions)
+FEATURES=(alloc libm lossy-conversions decimal defmt)
 echo "Testing supported features: ${FEATURES[*]}"
 
 NEXTEST_PROFILE=""
@@ -41,19 +41,22 @@ set -x
 cargo build
 cargo nextest run $NEXTEST_PROFILE
 
-# test no_std / no-default-features
-cargo build --no-default-features
-cargo nextest run $NEXTEST_PROFILE --no-default-features
+# test no_std / no-default-features, which needs the libm float maths
+cargo build --no-default-features --features=libm
+cargo nextest run $NEXTEST_PROFILE --no-default-features --features=libm
+cargo test --doc --no-default-features --features=libm
 
-# test each feature in isolation
+# test each feature in isolation, along with libm
 for feature in "${FEATURES[@]}"; do
-  cargo build --no-default-features --features="$feature"
-  cargo nextest run $NEXTEST_PROFILE --no-default-features --features="$feature"
+  cargo build --no-default-features --features="libm $feature"
+  cargo nextest run $NEXTEST_PROFILE --no-default-features --features="libm $feature"
+  cargo test --doc --no-default-features --features="libm $feature"
 done
 
 # test all features combined
 cargo build --features="${FEATURES[*]}"
 cargo nextest run $NEXTEST_PROFILE --features="${FEATURES[*]}"
+cargo test --doc --features="${FEATURES[*]}"
 
-# doc tests (not supported by nextest)
+# doc tests (not supported by nextest) with the default features
 cargo test --doc
@@ -1,14 +1,168 @@
 //! Defines the `Base` struct and methods.
 
+use core::fmt;
+
+use crate::format::Grouping;
+use crate::math;
+use crate::prefix::Prefix;
+
 /// Represents the base for units [Prefix](crate::prefix::Prefix).
 ///
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy)]
 pub enum Base {
     /// The most common base, where 1 k means `1000,` 1 M means `1000^2`, ...
     B1000,
     /// A very common base for bibytes, where 1 kiB means `1024`, 1 MiB means
     /// `1024 * 1024`, ...
     B1024,
+    /// The Indian numbering system, where 1 lakh means `1e5`, 1 crore means
+    /// `1e7`, 1 arab means `1e9`, ... Each step above the lakh is a factor
+    /// `100`.
+    ///
+    /// Values below 1 lakh are not scaled, and are grouped as `12,34,567`
+    /// (see [`Grouping::Indian`]).
+    Indian,
+    /// The East Asian myriad scale, where 1 万 means `1e4`, 1 億 means `1e8`,
+    /// 1 兆 means `1e12`, ... The symbols depend on the provided
+    /// [`MyriadSymbols`].
+    ///
+    /// Values below 1 万 are not scaled, there is no separator between the
+    /// mantissa and the symbol, as in `1.2万`, and digits are grouped by 4
+    /// (see [`Grouping::Myriad`]).
+    Myriad(MyriadSymbols),
+    /// A user-defined scale, see [`Scale`].
+    ///
+    /// Two custom bases are equal if they refer to the same scale, or if
+    /// their scales define the same ratio, steps, symbols, names, grouping
+    /// and separator.
+    Custom(&'static dyn Scale),
+}
+
+impl PartialEq for Base {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::B1000, Self::B1000) | (Self::B1024, Self::B1024) => true,
+            (Self::Indian, Self::Indian) => true,
+            (Self::Myriad(a), Self::Myriad(b)) => a == b,
+            (Self::Custom(a), Self::Custom(b)) => same_scale(*a, *b),
+            _ => false,
+        }
+    }
+}
+
+/// Compares two scales: by address and type first, then by definition,
+/// as the same scale may live at distinct addresses.
+fn same_scale(a: &dyn Scale, b: &dyn Scale) -> bool {
+    // Distinct zero-sized statics may share one address, but the vtables
+    // of distinct types differ, so equal wide pointers are the same scale.
+    if core::ptr::eq(a, b) {
+        return true;
+    }
+    let min_step = Prefix::Yocto as i32 / 3;
+    let max_step = Prefix::Yotta as i32 / 3;
+    a.ratio().to_bits() == b.ratio().to_bits()
+        && a.min_step() == b.min_step()
+        && a.max_step() == b.max_step()
+        && a.grouping() == b.grouping()
+        && a.separator() == b.separator()
+        && (min_step..=max_step)
+            .all(|step| a.symbol(step) == b.symbol(step) && a.name(step) == b.name(step))
+}
+
+impl Eq for Base {}
+
+/// Defines a user scale for [`Base::Custom`], such as sexagesimal time or
+/// labels for storage tiers.
+///
+/// A scale is a sequence of steps, each one `ratio()` times larger than the
+/// previous one. Steps are represented by a [`Prefix`] whose exponent is 3
+/// times the step: `Unit` is step 0, `Kilo` is step 1, `Milli` is step -1,
+/// etc. This way, custom bases work with
+/// [`Constraint`][`crate::prefix::Constraint`] and
+/// [`format_value!()`][`crate::format_value`] like the built-in ones.
+///
+/// # Example
+///
+/// ```
+/// use si_scale::prelude::{Base, Constraint, Value};
+/// use si_scale::base::Scale;
+/// use si_scale::format_value;
+///
+/// #[derive(Debug)]
+/// struct Sexagesimal;
+///
+/// impl Scale for Sexagesimal {
+///     fn ratio(&self) -> f64 {
+///         60.0
+///     }
+///     fn symbol(&self, step: i32) -> &'static str {
+///         match step {
+///             1 => "min",
+///             2 => "h",
+///             _ => "s",
+///         }
+///     }
+///     fn max_step(&self) -> i32 {
+///         2
+///     }
+/// }
+///
+/// static TIME: Sexagesimal = Sexagesimal;
+///
+/// let v = Value::new_with(5400, Base::Custom(&TIME), Constraint::None);
+/// assert_eq!(format!("{}", format_value!(v, "{:.1}")), "1.5 h");
+///
+/// let v = Value::new_with(5400, Base::Custom(&TIME), Constraint::UnitAndBelow);
+/// assert_eq!(format!("{}", format_value!(v, "{}")), "5400 s");
+/// ```
+pub trait Scale: fmt::Debug + Sync {
+    /// Returns the ratio between two consecutive steps, such as `60.0`.
+    ///
+    /// The ratio must be finite and greater than 1, otherwise values are
+    /// never scaled and stay at step 0, within the min and max steps.
+    fn ratio(&self) -> f64;
+
+    /// Returns the symbol of the provided `step`, such as `"min"`.
+    ///
+    /// The step `0` usually has the symbol of the unit itself, or an empty
+    /// one. This may be called with any step between `-8` and `8` if the
+    /// constraint forces it.
+    fn symbol(&self, step: i32) -> &'static str;
+
+    /// Returns the long name of the provided `step`. Defaults to the symbol.
+    fn name(&self, step: i32) -> &'static str {
+        self.symbol(step)
+    }
+
+    /// Returns the smallest step chosen for small values. Defaults to `0`.
+    fn min_step(&self) -> i32 {
+        0
+    }
+
+    /// Returns the largest step chosen for large values.
+    fn max_step(&self) -> i32;
+
+    /// Returns the digit grouping. Defaults to [`Grouping::Thousands`].
+    fn grouping(&self) -> Grouping {
+        Grouping::Thousands
+    }
+
+    /// Returns the separator between the mantissa and the symbol. Defaults
+    /// to a space.
+    fn separator(&self) -> &'static str {
+        " "
+    }
+}
+
+/// Selects the symbols used by [`Base::Myriad`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MyriadSymbols {
+    /// Simplified Chinese: 万, 亿, 兆, 京, ...
+    Simplified,
+    /// Traditional Chinese: 萬, 億, 兆, 京, ...
+    Traditional,
+    /// Japanese: 万, 億, 兆, 京, ...
+    Japanese,
 }
 
 impl Base {
@@ -41,28 +195,275 @@ impl Base {
             return 0;
         }
         match self {
-            Self::B1000 => (x.abs().log10() / 3f64).floor() as i32 * 3,
-            Self::B1024 => (x.abs().log2() / 10f64).floor() as i32 * 3,
+            Self::B1000 => math::floor(math::log10(math::abs(x)) / 3f64) as i32 * 3,
+            Self::B1024 => math::floor(math::log2(math::abs(x)) / 10f64) as i32 * 3,
+            Self::Indian => {
+                if math::abs(x) < 1e5 {
+                    0
+                } else {
+                    math::floor((math::log10(math::abs(x)) - 3f64) / 2f64) as i32 * 3
+                }
+            }
+            Self::Myriad(_) => {
+                if math::abs(x) < 1e4 {
+                    0
+                } else {
+                    math::floor(math::log10(math::abs(x)) / 4f64) as i32 * 3
+                }
+            }
+            Self::Custom(scale) => {
+                let min_step = scale.min_step().max(Prefix::Yocto as i32 / 3);
+                let max_step = scale.max_step().min(Prefix::Yotta as i32 / 3);
+                let ratio = scale.ratio();
+                if !(ratio > 1.0 && ratio.is_finite()) {
+                    return 0.clamp(min_step, max_step.max(min_step)) * 3;
+                }
+                // Clamped so that adjusting the step does not overflow, e.g.
+                // for infinite values.
+                let mut step = (math::floor(math::ln(math::abs(x)) / math::ln(ratio)) as i32)
+                    .clamp(Prefix::Yocto as i32 / 3 - 1, Prefix::Yotta as i32 / 3 + 1);
+                // Fix rounding errors of the logarithms, e.g. for 60^2.
+                if math::powi(ratio, step + 1) <= math::abs(x) {
+                    step += 1;
+                } else if math::powi(ratio, step) > math::abs(x) {
+                    step -= 1;
+                }
+                step.clamp(min_step, max_step.max(min_step)) * 3
+            }
         }
     }
 
+    /// Same as [`Base::integral_exponent_for()`], but for a number `x`
+    /// expressed in a unit raised to `power`, such as `2` for square meters.
+    ///
+    /// One prefix step then scales numbers by the base raised to `power`:
+    /// `1 km²` is `1e6 m²`, and `1 cm³` is `1e-6 m³`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use si_scale::base::Base;
+    ///
+    /// assert_eq!(Base::B1000.integral_exponent_for_power(5e6, 2), 3); // km²
+    /// assert_eq!(Base::B1000.integral_exponent_for_power(5e5, 2), 0); // m²
+    /// assert_eq!(Base::B1000.integral_exponent_for_power(2e-9, 3), -3); // mm³
+    /// ```
+    ///
+    pub fn integral_exponent_for_power<F>(&self, x: F, power: i32) -> i32
+    where
+        F: Into<f64>,
+    {
+        let x: f64 = x.into();
+        if x == 0.0 || power == 0 {
+            return 0;
+        }
+        if power == 1 {
+            return self.integral_exponent_for(x);
+        }
+        let fits = |exponent: i32| self.pow_with_power(exponent, power) <= math::abs(x);
+        // Start from the exponent of the root, then fix rounding errors. The
+        // scaling factor decreases with the exponent for negative powers.
+        let mut exponent =
+            self.integral_exponent_for(math::powf(math::abs(x), 1f64 / power as f64));
+        if power > 0 {
+            if fits(exponent + 3) {
+                exponent += 3;
+            } else if !fits(exponent) {
+                exponent -= 3;
+            }
+        } else if !fits(exponent) {
+            exponent += 3;
+        } else if fits(exponent - 3) {
+            exponent -= 3;
+        }
+        exponent
+    }
+
+    /// Returns the scaling factor of the provided `exponent` for a unit
+    /// raised to `power`, i.e. [`Base::pow()`] raised to `power`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use si_scale::base::Base;
+    ///
+    /// assert_eq!(Base::B1000.pow_with_power(3, 2), 1e6);
+    /// assert_eq!(Base::B1000.pow_with_power(-3, 3), 1e-9);
+    /// ```
+    ///
+    pub fn pow_with_power(&self, exponent: i32, power: i32) -> f64 {
+        math::powi(self.pow(exponent), power)
+    }
+
     /// This helper function returns a `f64` scaling factor for the mantissa,
     /// obtained by raising self to the power of the provided `exponent`
     /// divided by 3.
     ///
+    /// For `Indian`, positive exponents are steps of the lakh-crore scale, so
+    /// that `3` returns `1e5` (1 lakh), `6` returns `1e7` (1 crore), etc.
+    /// For `Myriad`, positive exponents are steps of `1e4`, so that `3`
+    /// returns `1e4` (1 万). Negative exponents of both are treated as in
+    /// base 1000.
+    ///
     /// # Example
     ///
     /// ```
     /// use si_scale::base::Base;
     ///
     /// assert_eq!(Base::B1000.pow(9), 1e9);
-    /// assert_eq!(Base::B1024.pow(3), 1024f64)
+    /// assert_eq!(Base::B1024.pow(3), 1024f64);
+    /// assert_eq!(Base::Indian.pow(6), 1e7);
     /// ```
     ///
     pub fn pow(&self, exponent: i32) -> f64 {
         match self {
-            Self::B1000 => 1000f64.powf(exponent as f64 / 3f64),
-            Self::B1024 => 1024f64.powf(exponent as f64 / 3f64),
+            Self::B1000 => math::powf(1000f64, exponent as f64 / 3f64),
+            Self::B1024 => math::powf(1024f64, exponent as f64 / 3f64),
+            Self::Indian => {
+                if exponent > 0 {
+                    math::powf(10f64, 2f64 * exponent as f64 / 3f64 + 3f64)
+                } else {
+                    math::powf(1000f64, exponent as f64 / 3f64)
+                }
+            }
+            Self::Myriad(_) => {
+                if exponent > 0 {
+                    math::powf(10f64, 4f64 * exponent as f64 / 3f64)
+                } else {
+                    math::powf(1000f64, exponent as f64 / 3f64)
+                }
+            }
+            Self::Custom(scale) => math::powf(scale.ratio(), exponent as f64 / 3f64),
+        }
+    }
+
+    /// Returns the symbol of the provided `prefix` in the self `Base`, such
+    /// as `"k"` in base 1000, `"ki"` in base 1024 or `"Cr"` (crore) in the
+    /// Indian base.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use si_scale::prelude::{Base, Prefix};
+    ///
+    /// assert_eq!(Base::B1000.prefix_symbol(Prefix::Mega), "M");
+    /// assert_eq!(Base::B1024.prefix_symbol(Prefix::Mega), "Mi");
+    /// assert_eq!(Base::Indian.prefix_symbol(Prefix::Kilo), "L");
+    /// assert_eq!(Base::Indian.prefix_symbol(Prefix::Unit), "");
+    /// ```
+    ///
+    pub fn prefix_symbol(&self, prefix: Prefix) -> &'static str {
+        match (self, prefix) {
+            (Self::B1024, Prefix::Yocto) => "yi",
+            (Self::B1024, Prefix::Zepto) => "zi",
+            (Self::B1024, Prefix::Atto) => "ai",
+            (Self::B1024, Prefix::Femto) => "fi",
+            (Self::B1024, Prefix::Pico) => "pi",
+            (Self::B1024, Prefix::Nano) => "ni",
+            (Self::B1024, Prefix::Micro) => "µi",
+            (Self::B1024, Prefix::Milli) => "mi",
+            (Self::B1024, Prefix::Kilo) => "ki",
+            (Self::B1024, Prefix::Mega) => "Mi",
+            (Self::B1024, Prefix::Giga) => "Gi",
+            (Self::B1024, Prefix::Tera) => "Ti",
+            (Self::B1024, Prefix::Peta) => "Pi",
+            (Self::B1024, Prefix::Exa) => "Ei",
+            (Self::B1024, Prefix::Zetta) => "Zi",
+            (Self::B1024, Prefix::Yotta) => "Yi",
+            (Self::Indian, Prefix::Kilo) => "L",
+            (Self::Indian, Prefix::Mega) => "Cr",
+            (Self::Indian, Prefix::Giga) => "Ar",
+            (Self::Indian, Prefix::Tera) => "Kh",
+            (Self::Indian, Prefix::Peta) => "Nl",
+            (Self::Indian, Prefix::Exa) => "Pd",
+            (Self::Indian, Prefix::Zetta) => "Sh",
+            (Self::Indian, Prefix::Yotta) => "MSh",
+            (Self::Myriad(symbols), _) if prefix.exponent() > 0 => symbols.symbol(prefix),
+            (Self::Custom(scale), _) => scale.symbol(prefix.exponent() / 3),
+            _ => (&prefix).into(),
+        }
+    }
+
+    /// Returns the separator printed between the mantissa and the prefix
+    /// symbol (or the unit): a space, except for the myriad scale, which
+    /// displays `1.2万`, and custom scales which define their own.
+    pub fn separator(&self) -> &'static str {
+        match self {
+            Self::Myriad(_) => "",
+            Self::Custom(scale) => scale.separator(),
+            _ => " ",
+        }
+    }
+
+    /// Returns the long name of the provided `prefix` in the self `Base`,
+    /// such as `"kilo"` in base 1000 or `"kibi"` in base 1024.
+    ///
+    /// Base 1024 only defines names for prefixes above `Unit`; the SI names
+    /// are returned for the other ones.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use si_scale::prelude::{Base, Prefix};
+    ///
+    /// assert_eq!(Base::B1000.prefix_long_name(Prefix::Mega), "mega");
+    /// assert_eq!(Base::B1024.prefix_long_name(Prefix::Mega), "mebi");
+    /// ```
+    ///
+    pub fn prefix_long_name(&self, prefix: Prefix) -> &'static str {
+        match (self, prefix) {
+            (Self::B1024, Prefix::Kilo) => "kibi",
+            (Self::B1024, Prefix::Mega) => "mebi",
+            (Self::B1024, Prefix::Giga) => "gibi",
+            (Self::B1024, Prefix::Tera) => "tebi",
+            (Self::B1024, Prefix::Peta) => "pebi",
+            (Self::B1024, Prefix::Exa) => "exbi",
+            (Self::B1024, Prefix::Zetta) => "zebi",
+            (Self::B1024, Prefix::Yotta) => "yobi",
+            (Self::Indian, Prefix::Kilo) => "lakh",
+            (Self::Indian, Prefix::Mega) => "crore",
+            (Self::Indian, Prefix::Giga) => "arab",
+            (Self::Indian, Prefix::Tera) => "kharab",
+            (Self::Indian, Prefix::Peta) => "neel",
+            (Self::Indian, Prefix::Exa) => "padma",
+            (Self::Indian, Prefix::Zetta) => "shankh",
+            (Self::Indian, Prefix::Yotta) => "mahashankh",
+            (Self::Myriad(symbols), _) if prefix.exponent() > 0 => symbols.symbol(prefix),
+            (Self::Custom(scale), _) => scale.name(prefix.exponent() / 3),
+            _ => prefix.long_name(),
+        }
+    }
+
+    /// Returns the digit grouping conventionally used with the self `Base`,
+    /// see [`separated_float_with()`][`crate::format::separated_float_with()`].
+    pub fn grouping(&self) -> Grouping {
+        match self {
+            Self::B1000 | Self::B1024 => Grouping::Thousands,
+            Self::Indian => Grouping::Indian,
+            Self::Myriad(_) => Grouping::Myriad,
+            Self::Custom(scale) => scale.grouping(),
+        }
+    }
+}
+
+impl MyriadSymbols {
+    /// Returns the symbol of the provided positive `prefix`, where `Kilo`
+    /// stands for 万 (`1e4`), `Mega` for 億 (`1e8`), etc.
+    fn symbol(&self, prefix: Prefix) -> &'static str {
+        match (self, prefix) {
+            (Self::Traditional, Prefix::Kilo) => "萬",
+            (_, Prefix::Kilo) => "万",
+            (Self::Simplified, Prefix::Mega) => "亿",
+            (_, Prefix::Mega) => "億",
+            (_, Prefix::Giga) => "兆",
+            (_, Prefix::Tera) => "京",
+            (_, Prefix::Peta) => "垓",
+            (Self::Japanese, Prefix::Exa) => "𥝱",
+            (_, Prefix::Exa) => "秭",
+            (_, Prefix::Zetta) => "穰",
+            (Self::Simplified, Prefix::Yotta) => "沟",
+            (_, Prefix::Yotta) => "溝",
+            _ => "",
         }
     }
 }
@@ -71,6 +472,30 @@ impl Base {
 mod tests {
     use super::*;
 
+    #[test]
+    fn integral_exponents_for_powers() {
+        let base = Base::B1000;
+        assert_eq!(base.integral_exponent_for_power(1e6, 2), 3);
+        assert_eq!(base.integral_exponent_for_power(999_999.0, 2), 0);
+        assert_eq!(base.integral_exponent_for_power(-1e6, 2), 3);
+        assert_eq!(base.integral_exponent_for_power(1e9, 3), 3);
+        assert_eq!(base.integral_exponent_for_power(1e-6, 2), -3);
+        assert_eq!(base.integral_exponent_for_power(1e-7, 2), -6);
+        assert_eq!(base.integral_exponent_for_power(1e-6, 3), -3);
+        assert_eq!(base.integral_exponent_for_power(1.5e3, 1), 3);
+        assert_eq!(base.integral_exponent_for_power(0.0, 2), 0);
+
+        // Per meter: 0.25 per meter is 250 per kilometer.
+        assert_eq!(base.integral_exponent_for_power(0.25, -1), 3);
+        assert_eq!(base.integral_exponent_for_power(1.0, -1), 0);
+        assert_eq!(base.integral_exponent_for_power(2e3, -1), -3);
+
+        assert_eq!(
+            Base::B1024.integral_exponent_for_power(1024.0 * 1024.0, 2),
+            3
+        );
+    }
+
     #[test]
     fn exponent_of_zero_is_zero() {
         assert_eq!(0, Base::B1000.integral_exponent_for(0.0));
@@ -79,4 +504,194 @@ mod tests {
         assert_eq!(0, Base::B1024.integral_exponent_for(0.0));
         assert_eq!(0, Base::B1024.integral_exponent_for(-0.0));
     }
+
+    #[test]
+    fn indian_exponents() {
+        assert_eq!(0, Base::Indian.integral_exponent_for(0.5));
+        assert_eq!(0, Base::Indian.integral_exponent_for(99_999.0));
+        assert_eq!(3, Base::Indian.integral_exponent_for(1e5));
+        assert_eq!(3, Base::Indian.integral_exponent_for(-9_999_999.0));
+        assert_eq!(6, Base::Indian.integral_exponent_for(1e7));
+        assert_eq!(9, Base::Indian.integral_exponent_for(4.5e9));
+
+        assert_eq!(Base::Indian.pow(0), 1.0);
+        assert_eq!(Base::Indian.pow(3), 1e5);
+        assert_eq!(Base::Indian.pow(9), 1e9);
+        assert_eq!(Base::Indian.pow(-3), 1e-3);
+    }
+
+    #[test]
+    fn myriad_exponents() {
+        let base = Base::Myriad(MyriadSymbols::Japanese);
+        assert_eq!(0, base.integral_exponent_for(0.5));
+        assert_eq!(0, base.integral_exponent_for(9_999.0));
+        assert_eq!(3, base.integral_exponent_for(1e4));
+        assert_eq!(3, base.integral_exponent_for(-99_999_999.0));
+        assert_eq!(6, base.integral_exponent_for(1e8));
+        assert_eq!(9, base.integral_exponent_for(3.4e12));
+
+        assert_eq!(base.pow(0), 1.0);
+        assert_eq!(base.pow(3), 1e4);
+        assert_eq!(base.pow(6), 1e8);
+        assert_eq!(base.pow(-3), 1e-3);
+    }
+
+    #[test]
+    fn myriad_symbols() {
+        let base = Base::Myriad(MyriadSymbols::Simplified);
+        assert_eq!(base.prefix_symbol(Prefix::Unit), "");
+        assert_eq!(base.prefix_symbol(Prefix::Kilo), "万");
+        assert_eq!(base.prefix_symbol(Prefix::Mega), "亿");
+        assert_eq!(base.prefix_symbol(Prefix::Yotta), "沟");
+
+        let base = Base::Myriad(MyriadSymbols::Traditional);
+        assert_eq!(base.prefix_symbol(Prefix::Kilo), "萬");
+        assert_eq!(base.prefix_symbol(Prefix::Mega), "億");
+
+        let base = Base::Myriad(MyriadSymbols::Japanese);
+        assert_eq!(base.prefix_symbol(Prefix::Kilo), "万");
+        assert_eq!(base.prefix_symbol(Prefix::Mega), "億");
+        assert_eq!(base.prefix_symbol(Prefix::Giga), "兆");
+        assert_eq!(base.prefix_long_name(Prefix::Giga), "兆");
+    }
+
+    #[derive(Debug)]
+    struct Tiers;
+
+    impl Scale for Tiers {
+        fn ratio(&self) -> f64 {
+            1000.0
+        }
+        fn symbol(&self, step: i32) -> &'static str {
+            match step {
+                0 => "hot",
+                1 => "warm",
+                _ => "cold",
+            }
+        }
+        fn name(&self, step: i32) -> &'static str {
+            match step {
+                0 => "hot tier",
+                1 => "warm tier",
+                _ => "cold tier",
+            }
+        }
+        fn max_step(&self) -> i32 {
+            2
+        }
+    }
+
+    #[derive(Debug)]
+    struct Sexagesimal;
+
+    impl Scale for Sexagesimal {
+        fn ratio(&self) -> f64 {
+            60.0
+        }
+        fn symbol(&self, _step: i32) -> &'static str {
+            ""
+        }
+        fn min_step(&self) -> i32 {
+            -2
+        }
+        fn max_step(&self) -> i32 {
+            2
+        }
+    }
+
+    static TIERS: Tiers = Tiers;
+    static SEXAGESIMAL: Sexagesimal = Sexagesimal;
+
+    #[test]
+    fn custom_exponents() {
+        let base = Base::Custom(&SEXAGESIMAL);
+        assert_eq!(0, base.integral_exponent_for(0.0));
+        assert_eq!(0, base.integral_exponent_for(59.0));
+        assert_eq!(3, base.integral_exponent_for(60.0));
+        assert_eq!(3, base.integral_exponent_for(-3599.0));
+        assert_eq!(6, base.integral_exponent_for(3600.0));
+        assert_eq!(6, base.integral_exponent_for(1e9));
+        assert_eq!(-3, base.integral_exponent_for(0.5));
+        assert_eq!(-6, base.integral_exponent_for(1e-9));
+        assert_eq!(6, base.integral_exponent_for(f64::INFINITY));
+        assert_eq!(6, base.integral_exponent_for(f64::NEG_INFINITY));
+
+        assert_eq!(base.pow(6), 3600.0);
+        assert_eq!(base.pow(-3), 1.0 / 60.0);
+    }
+
+    #[derive(Debug)]
+    struct Invalid(f64);
+
+    impl Scale for Invalid {
+        fn ratio(&self) -> f64 {
+            self.0
+        }
+        fn symbol(&self, _step: i32) -> &'static str {
+            ""
+        }
+        fn min_step(&self) -> i32 {
+            -2
+        }
+        fn max_step(&self) -> i32 {
+            2
+        }
+    }
+
+    static ONE: Invalid = Invalid(1.0);
+    static HALF: Invalid = Invalid(0.5);
+    static NAN: Invalid = Invalid(f64::NAN);
+
+    #[test]
+    fn custom_invalid_ratios() {
+        for scale in [&ONE, &HALF, &NAN] {
+            let base = Base::Custom(scale);
+            assert_eq!(0, base.integral_exponent_for(1e9), "{:?}", scale);
+            assert_eq!(0, base.integral_exponent_for(1e-9), "{:?}", scale);
+        }
+    }
+
+    #[test]
+    fn custom_symbols() {
+        let base = Base::Custom(&TIERS);
+        assert_eq!(base.prefix_symbol(Prefix::Unit), "hot");
+        assert_eq!(base.prefix_symbol(Prefix::Giga), "cold");
+        assert_eq!(base.prefix_long_name(Prefix::Kilo), "warm tier");
+        assert_eq!(base.grouping(), Grouping::Thousands);
+        assert_eq!(base.separator(), " ");
+    }
+
+    #[test]
+    fn custom_equality() {
+        assert_eq!(Base::Custom(&TIERS), Base::Custom(&TIERS));
+        assert_ne!(Base::Custom(&TIERS), Base::Custom(&SEXAGESIMAL));
+        assert_ne!(Base::Custom(&ONE), Base::Custom(&HALF));
+        assert_eq!(Base::Custom(&NAN), Base::Custom(&NAN));
+        static TIERS_COPY: Tiers = Tiers;
+        assert_eq!(Base::Custom(&TIERS), Base::Custom(&TIERS_COPY));
+        assert_ne!(Base::Custom(&TIERS), Base::B1000);
+        assert_eq!(Base::B1000, Base::B1000);
+        assert_ne!(Base::B1000, Base::B1024);
+    }
+
+    #[test]
+    fn prefix_symbols() {
+        assert_eq!(Base::B1000.prefix_symbol(Prefix::Micro), "µ");
+        assert_eq!(Base::B1000.prefix_symbol(Prefix::Unit), "");
+        assert_eq!(Base::B1024.prefix_symbol(Prefix::Kilo), "ki");
+        assert_eq!(Base::B1024.prefix_symbol(Prefix::Unit), "");
+        assert_eq!(Base::Indian.prefix_symbol(Prefix::Mega), "Cr");
+    }
+
+    #[test]
+    fn prefix_long_names() {
+        assert_eq!(Base::B1000.prefix_long_name(Prefix::Kilo), "kilo");
+        assert_eq!(Base::B1000.prefix_long_name(Prefix::Unit), "");
+        assert_eq!(Base::B1024.prefix_long_name(Prefix::Kilo), "kibi");
+        assert_eq!(Base::B1024.prefix_long_name(Prefix::Yotta), "yobi");
+        assert_eq!(Base::B1024.prefix_long_name(Prefix::Unit), "");
+        assert_eq!(Base::B1024.prefix_long_name(Prefix::Milli), "milli");
+        assert_eq!(Base::Indian.prefix_long_name(Prefix::Kilo), "lakh");
+        assert_eq!(Base::Indian.prefix_long_name(Prefix::Giga), "arab");
+    }
 }
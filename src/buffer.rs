@@ -0,0 +1,466 @@
+//! Formatting into caller-provided byte buffers.
+//!
+//! A [`BufferFormat`] writes a [`Value`] with an optional precision, digit
+//! groupings as done by [`separated_float()`][`crate::format::separated_float`],
+//! the prefix symbol and a unit, without allocating. It is written either
+//! into a `&mut [u8]` with [`BufferFormat::write_to()`], which returns the
+//! written `&str`, or into any `fmt::Write` sink through its `Display`
+//! implementation.
+//!
+//! The length of the output is bounded: for `f64`, `f32` and `Decimal`
+//! mantissas and the built-in bases, a buffer of [`MAX_LEN`] bytes, or
+//! [`MAX_GROUPED_LEN`] bytes with groupings, plus the length of the unit,
+//! never overflows. [`max_len()`] gives the tighter bound of a given
+//! configuration, which can size a buffer in a `const` context.
+//!
+//! # Example
+//!
+//! ```
+//! use si_scale::buffer::{max_len, BufferFormat};
+//! use si_scale::prelude::{Base, Constraint, Value};
+//!
+//! const LEN: usize = max_len(Some(1), None) + "B".len();
+//!
+//! let value = Value::new_with(1_234_567, Base::B1000, Constraint::UnitAndAbove);
+//! let mut buf = [0u8; LEN];
+//! let written = BufferFormat::new(value).precision(1).unit("B");
+//! assert_eq!(written.write_to(&mut buf).unwrap(), "1.2 MB");
+//!
+//! let value = Value::new_with(1234.5678, Base::B1000, Constraint::UnitAndBelow);
+//! let written = BufferFormat::new(value).precision(5).groupings('_').unit("s");
+//! assert_eq!(written.write_to(&mut buf).unwrap(), "1_234.567_80 s");
+//! assert!(written.write_to(&mut buf[..8]).is_err());
+//! ```
+
+use core::fmt::{self, Write};
+
+use crate::format::write_separated_float;
+use crate::value::{Mantissa, Value};
+
+/// Maximum precision honored by a [`BufferFormat`], larger precisions are
+/// reduced to it.
+pub const MAX_PRECISION: usize = 64;
+
+/// Maximum length of the separator and prefix symbol of the built-in bases,
+/// reached by `" µi"`, `" MSh"` or the 4-byte myriad symbol `"𥝱"`. Custom
+/// scales may use longer symbols.
+pub const MAX_PREFIX_LEN: usize = 4;
+
+/// Maximum length of a `BufferFormat` without groupings, for any precision,
+/// excluding the unit.
+pub const MAX_LEN: usize = max_len(Some(MAX_PRECISION), None);
+
+/// Maximum length of a `BufferFormat` with groupings, for any precision and
+/// any separator, excluding the unit.
+pub const MAX_GROUPED_LEN: usize = max_len(Some(MAX_PRECISION), Some(char::MAX));
+
+/// Number of integral digits of `f64::MAX`.
+const MAX_INTEGRAL_DIGITS: usize = 309;
+
+/// Maximum length of a mantissa written with a precision: a sign, the
+/// integral digits and a decimal point, followed by the fractional digits.
+const MANTISSA_LEN: usize = 1 + MAX_INTEGRAL_DIGITS + 1;
+
+/// Maximum length of a mantissa written without precision, reached by
+/// `-5e-324` which is written as `-0.` followed by 324 digits.
+const SHORTEST_MANTISSA_LEN: usize = 327;
+
+/// Returns the maximum length of a [`BufferFormat`] with the provided
+/// precision and groupings separator, excluding the unit, for `f64`, `f32`
+/// and `Decimal` mantissas and the built-in bases.
+///
+/// # Example
+///
+/// ```
+/// use si_scale::buffer::{max_len, MAX_GROUPED_LEN, MAX_LEN};
+///
+/// assert_eq!(max_len(Some(2), None), 317);
+/// assert!(max_len(None, None) <= MAX_LEN);
+/// assert!(max_len(Some(3), Some('_')) <= MAX_GROUPED_LEN);
+/// ```
+pub const fn max_len(precision: Option<usize>, separator: Option<char>) -> usize {
+    let (mantissa_len, fraction_separators) = match precision {
+        // Without precision, a mantissa with more than 17 integral digits is
+        // an integer, so the 107 separators of the 324 fractional digits of
+        // the smallest subnormal never add to those of the integral part.
+        None => (SHORTEST_MANTISSA_LEN, 0),
+        Some(precision) => {
+            let precision = if precision < MAX_PRECISION {
+                precision
+            } else {
+                MAX_PRECISION
+            };
+            (MANTISSA_LEN + precision, precision.saturating_sub(1) / 3)
+        }
+    };
+    let separators_len = match separator {
+        // The Indian grouping inserts the most separators in the integral
+        // part: after the first 3 digits, then every 2 digits.
+        Some(separator) => {
+            let integral_separators = 1 + (MAX_INTEGRAL_DIGITS - 4) / 2;
+            (integral_separators + fraction_separators) * separator.len_utf8()
+        }
+        None => 0,
+    };
+    mantissa_len + separators_len + MAX_PREFIX_LEN
+}
+
+/// Formats a [`Value`] without allocating, see the [module
+/// documentation][`crate::buffer`].
+///
+/// Without unit, the output is the same as
+/// [`format_value!()`][`crate::format_value`] with `no_unit`, such as
+/// `"1.5 ki"`; with a unit, it is the same as the predefined helpers, such as
+/// `"16 B"`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BufferFormat<'a, T = f64> {
+    value: Value<T>,
+    precision: Option<usize>,
+    separator: Option<char>,
+    unit: &'a str,
+}
+
+impl<T: Mantissa> BufferFormat<'static, T> {
+    /// Formats `value` with all the digits of its mantissa and without unit.
+    pub fn new(value: Value<T>) -> Self {
+        Self {
+            value,
+            precision: None,
+            separator: None,
+            unit: "",
+        }
+    }
+}
+
+impl<'a, T: Mantissa> BufferFormat<'a, T> {
+    /// Writes the mantissa with `precision` decimals, as with `"{:.2}"`. The
+    /// precision is reduced to [`MAX_PRECISION`].
+    pub fn precision(self, precision: usize) -> Self {
+        Self {
+            precision: Some(precision.min(MAX_PRECISION)),
+            ..self
+        }
+    }
+
+    /// Groups the digits of the mantissa with `separator`, according to the
+    /// grouping of the value's base.
+    pub fn groupings(self, separator: char) -> Self {
+        Self {
+            separator: Some(separator),
+            ..self
+        }
+    }
+
+    /// Writes `unit` after the prefix symbol.
+    pub fn unit<'b>(self, unit: &'b str) -> BufferFormat<'b, T> {
+        BufferFormat {
+            value: self.value,
+            precision: self.precision,
+            separator: self.separator,
+            unit,
+        }
+    }
+
+    /// Returns the maximum length of the output of self, see [`max_len()`].
+    pub fn max_len(&self) -> usize {
+        max_len(self.precision, self.separator) + self.unit.len()
+    }
+
+    /// Writes self into `buf`, and returns the written string, or
+    /// `fmt::Error` if `buf` is too small.
+    pub fn write_to<'b>(&self, buf: &'b mut [u8]) -> Result<&'b str, fmt::Error> {
+        let mut writer = SliceWriter::new(buf);
+        write!(writer, "{}", self)?;
+        Ok(writer.into_str())
+    }
+
+    fn write_mantissa<W: Write>(&self, w: &mut W) -> fmt::Result {
+        match self.precision {
+            Some(precision) => write!(w, "{:.*}", precision, self.value.mantissa),
+            None => write!(w, "{}", self.value.mantissa),
+        }
+    }
+
+    /// Writes the mantissa, the prefix symbol and the unit into `w`.
+    fn write_parts<W: Write>(&self, w: &mut W) -> fmt::Result {
+        match self.separator {
+            Some(separator) => {
+                let mut digits = [0u8; MANTISSA_LEN + MAX_PRECISION];
+                let mut digits = SliceWriter::new(&mut digits);
+                self.write_mantissa(&mut digits)?;
+                write_separated_float(w, digits.as_str(), separator, self.value.base.grouping())?;
+            }
+            None => self.write_mantissa(w)?,
+        }
+
+        let symbol = self.value.base.prefix_symbol(self.value.prefix);
+        if self.unit.is_empty() && symbol.is_empty() {
+            return Ok(());
+        }
+        write!(w, "{}{}{}", self.value.base.separator(), symbol, self.unit)
+    }
+}
+
+/// Counts the characters written, to pad the output without buffering it.
+struct CharCount(usize);
+
+impl Write for CharCount {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.0 += s.chars().count();
+        Ok(())
+    }
+}
+
+impl<T: Mantissa> fmt::Display for BufferFormat<'_, T> {
+    /// Writes the mantissa, the prefix symbol and the unit, without
+    /// allocating. With groupings, fails if the mantissa is longer than the
+    /// longest `f64` mantissa, which only happens with custom mantissa types.
+    ///
+    /// Width, fill and alignment pad the whole output, left-aligned by
+    /// default; the precision is set with [`BufferFormat::precision()`].
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        pad_with(f, |mut w| self.write_parts(&mut w))
+    }
+}
+
+/// Writes the output of `write` padded to the width of the formatter, with
+/// its fill and alignment, left-aligned by default. The output is written
+/// twice when padding, first only to count its characters.
+///
+/// Unlike `Formatter::pad()`, the precision does not truncate the output:
+/// callers apply it to the mantissa.
+pub(crate) fn pad_with<F>(f: &mut fmt::Formatter<'_>, write: F) -> fmt::Result
+where
+    F: Fn(&mut dyn Write) -> fmt::Result,
+{
+    let width = match f.width() {
+        Some(width) => width,
+        None => return write(f),
+    };
+    let mut count = CharCount(0);
+    write(&mut count)?;
+    let padding = width.saturating_sub(count.0);
+    let (before, after) = match f.align() {
+        Some(fmt::Alignment::Right) => (padding, 0),
+        Some(fmt::Alignment::Center) => (padding / 2, padding - padding / 2),
+        _ => (0, padding),
+    };
+    let fill = f.fill();
+    for _ in 0..before {
+        f.write_char(fill)?;
+    }
+    write(f)?;
+    for _ in 0..after {
+        f.write_char(fill)?;
+    }
+    Ok(())
+}
+
+/// A `fmt::Write` sink into a byte slice, which fails instead of writing a
+/// partial string when the slice is full. Note that a failed `write!()` may
+/// still have written its first arguments.
+///
+/// # Example
+///
+/// ```
+/// use core::fmt::Write;
+/// use si_scale::buffer::SliceWriter;
+/// use si_scale::prelude::Value;
+///
+/// let mut buf = [0u8; 16];
+/// let mut writer = SliceWriter::new(&mut buf);
+/// write!(writer, "{}s", Value::from(1.3e-5)).unwrap();
+/// assert_eq!(writer.as_str(), "13 µs");
+/// assert!(writer.write_str(" is too long").is_err());
+/// assert_eq!(writer.into_str(), "13 µs");
+/// ```
+#[derive(Debug)]
+pub struct SliceWriter<'b> {
+    buf: &'b mut [u8],
+    len: usize,
+}
+
+impl<'b> SliceWriter<'b> {
+    /// Returns a writer at the start of `buf`.
+    pub fn new(buf: &'b mut [u8]) -> Self {
+        Self { buf, len: 0 }
+    }
+
+    /// Returns the number of bytes written.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if nothing was written.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the written string.
+    pub fn as_str(&self) -> &str {
+        // Only whole strings are written, so the bytes are valid UTF-8.
+        core::str::from_utf8(&self.buf[..self.len]).unwrap_or_default()
+    }
+
+    /// Returns the written string, borrowed from the buffer.
+    pub fn into_str(self) -> &'b str {
+        core::str::from_utf8(&self.buf[..self.len]).unwrap_or_default()
+    }
+
+    /// Writes `c` `count` times.
+    pub(crate) fn write_repeated(&mut self, c: char, count: usize) -> fmt::Result {
+        (0..count).try_for_each(|_| self.write_char(c))
+    }
+}
+
+impl Write for SliceWriter<'_> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let end = self.len + s.len();
+        self.buf
+            .get_mut(self.len..end)
+            .ok_or(fmt::Error)?
+            .copy_from_slice(s.as_bytes());
+        self.len = end;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::base::{Base, MyriadSymbols};
+    use crate::prefix::Constraint;
+
+    #[test]
+    fn buffer_formats() {
+        let mut buf = [0u8; MAX_GROUPED_LEN];
+
+        let value: Value = 5.3e5.into();
+        let format = BufferFormat::new(value);
+        assert_eq!(format.write_to(&mut buf), Ok("530 k"));
+        let value: Value = 16.0.into();
+        let format = BufferFormat::new(value);
+        assert_eq!(format.write_to(&mut buf), Ok("16"));
+        assert_eq!(format.unit("B").write_to(&mut buf), Ok("16 B"));
+
+        let value = Value::new_with(1.234567e-6, Base::B1000, Constraint::UnitAndBelow);
+        let format = BufferFormat::new(value).precision(3).unit("s");
+        assert_eq!(format.write_to(&mut buf), Ok("1.235 µs"));
+
+        let value = Value::new_with(3u32 << 30, Base::B1024, Constraint::UnitAndAbove);
+        let format = BufferFormat::new(value).precision(1).unit("B");
+        assert_eq!(format.write_to(&mut buf), Ok("3.0 GiB"));
+    }
+
+    #[test]
+    fn buffer_formats_with_groupings() {
+        let mut buf = [0u8; MAX_GROUPED_LEN];
+
+        let value = Value::new_with(1234.5678, Base::B1000, Constraint::UnitOnly);
+        let format = BufferFormat::new(value).groupings('_');
+        assert_eq!(format.write_to(&mut buf), Ok("1_234.567_8"));
+        assert_eq!(format.unit("m").write_to(&mut buf), Ok("1_234.567_8 m"));
+
+        let value = Value::new_with(-123_456_789, Base::Indian, Constraint::UnitOnly);
+        let format = BufferFormat::new(value).precision(2).groupings(',');
+        assert_eq!(format.write_to(&mut buf), Ok("-12,34,56,789.00"));
+
+        let base = Base::Myriad(MyriadSymbols::Japanese);
+        let value = Value::new_with(123_456_789, base, Constraint::UnitOnly);
+        let format = BufferFormat::new(value).groupings(',').unit("円");
+        assert_eq!(format.write_to(&mut buf), Ok("1,2345,6789円"));
+    }
+
+    #[test]
+    fn buffer_formats_with_padding() {
+        let value = Value::new_with(1.234567e-6, Base::B1000, Constraint::UnitAndBelow);
+        let format = BufferFormat::new(value).precision(1).unit("s");
+        assert_eq!(format!("{:>9}|", format), "   1.2 µs|");
+        assert_eq!(format!("{:9}|", format), "1.2 µs   |");
+        assert_eq!(format!("{:*^10}", format), "**1.2 µs**");
+        assert_eq!(format!("{:3}", format), "1.2 µs");
+
+        let value = Value::new_with(1234, Base::B1000, Constraint::UnitOnly);
+        let format = BufferFormat::new(value).groupings('_');
+        assert_eq!(format!("{:>7}", format), "  1_234");
+    }
+
+    #[test]
+    fn buffer_formats_with_custom_unit_symbols() {
+        use crate::base::Scale;
+
+        #[derive(Debug)]
+        struct Tiers;
+
+        impl Scale for Tiers {
+            fn ratio(&self) -> f64 {
+                1000.0
+            }
+            fn symbol(&self, step: i32) -> &'static str {
+                match step {
+                    0 => "hot",
+                    _ => "cold",
+                }
+            }
+            fn max_step(&self) -> i32 {
+                1
+            }
+        }
+
+        static TIERS: Tiers = Tiers;
+
+        let mut buf = [0u8; MAX_LEN];
+        let value = Value::new_with(5, Base::Custom(&TIERS), Constraint::None);
+        let format = BufferFormat::new(value);
+        assert_eq!(format.write_to(&mut buf), Ok("5 hot"));
+        assert_eq!(format.unit("B").write_to(&mut buf), Ok("5 hotB"));
+    }
+
+    #[test]
+    fn buffer_too_small() {
+        let value: Value = 1.3e-5.into();
+        let format = BufferFormat::new(value).unit("s");
+        let mut buf = [0u8; 6];
+        assert_eq!(format.write_to(&mut buf), Ok("13 µs"));
+        assert_eq!(format.write_to(&mut buf[..5]), Err(fmt::Error));
+    }
+
+    #[test]
+    fn worst_case_lengths() {
+        let cases = [
+            Value::new_with(-f64::MAX, Base::B1000, Constraint::UnitOnly),
+            Value::new_with(-5e-324, Base::B1000, Constraint::UnitOnly),
+            Value::new_with(-f64::MAX, Base::Indian, Constraint::UnitOnly),
+            Value::new_with(-5e-324, Base::Indian, Constraint::UnitOnly),
+            Value::new_with(-1.2345678901234567e16, Base::Indian, Constraint::UnitOnly),
+            Value::new_with(-1.2345678901234567e-300, Base::Indian, Constraint::UnitOnly),
+            Value::new_with(f64::MAX, Base::B1024, Constraint::None),
+            Value::new_with(
+                -1e300,
+                Base::Myriad(MyriadSymbols::Japanese),
+                Constraint::None,
+            ),
+        ];
+        let mut buf = [0u8; MAX_GROUPED_LEN];
+        for value in cases {
+            for precision in [None, Some(0), Some(3), Some(MAX_PRECISION)] {
+                for separator in [None, Some('_'), Some(char::MAX)] {
+                    let mut format = BufferFormat::new(value);
+                    if let Some(precision) = precision {
+                        format = format.precision(precision);
+                    }
+                    if let Some(separator) = separator {
+                        format = format.groupings(separator);
+                    }
+                    let len = format.write_to(&mut buf).unwrap().len();
+                    assert!(len <= format.max_len(), "{:?}", format);
+                    assert!(len <= MAX_GROUPED_LEN, "{:?}", format);
+                    if separator.is_none() {
+                        assert!(len <= MAX_LEN, "{:?}", format);
+                    }
+                }
+            }
+        }
+        assert_eq!(max_len(Some(1000), None), MAX_LEN);
+    }
+}
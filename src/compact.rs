@@ -0,0 +1,278 @@
+//! Compact notation for non-technical audiences, such as `1.2K`, `3.4M` or
+//! `5.6B`, as seen on social media counters, or spelled out as in
+//! `3 billion`.
+//!
+//! This differs from the SI notation: `1e9` is a "billion" (`B`) rather than
+//! "giga" (`G`), and `1e3` is a capitalized `K`. Values are scaled exactly
+//! like a [`Value`] in base 1000 with the `UnitAndAbove` constraint, only the
+//! suffixes change; they are defined by a [`Notation`].
+//!
+//! # Example
+//!
+//! ```
+//! use si_scale::compact::{Compact, LONG_SCALE};
+//!
+//! assert_eq!(format!("{:.1}", Compact::new(1234)), "1.2K");
+//! assert_eq!(format!("{}", Compact::new(3.4e6)), "3.4M");
+//! assert_eq!(format!("{}", Compact::new(5.6e9)), "5.6B");
+//! assert_eq!(format!("{}", Compact::new(999)), "999");
+//!
+//! assert_eq!(format!("{}", Compact::new(1.2e6).words()), "1.2 million");
+//! assert_eq!(
+//!     format!("{}", Compact::new(3e9).notation(&LONG_SCALE).words()),
+//!     "3 milliard"
+//! );
+//! ```
+
+use core::fmt;
+
+use crate::base::Base;
+use crate::prefix::{Constraint, Prefix};
+use crate::value::{IntoF64, Value};
+
+/// Defines the suffixes of a compact notation, from `1` to `1e24` by steps
+/// of `1000`.
+///
+/// The first entries are used for numbers below `1000`, and are usually
+/// empty.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Notation {
+    /// Short suffixes, appended without a space, such as `"K"`.
+    pub symbols: [&'static str; 9],
+    /// Spelled-out suffixes, appended after a space, such as `"thousand"`.
+    pub words: [&'static str; 9],
+}
+
+/// The short scale, used in English: `1e9` is a billion.
+pub const SHORT_SCALE: Notation = Notation {
+    symbols: ["", "K", "M", "B", "T", "Qa", "Qi", "Sx", "Sp"],
+    words: [
+        "",
+        "thousand",
+        "million",
+        "billion",
+        "trillion",
+        "quadrillion",
+        "quintillion",
+        "sextillion",
+        "septillion",
+    ],
+};
+
+/// The long scale, used in most of continental Europe: `1e9` is a milliard
+/// and `1e12` a billion.
+pub const LONG_SCALE: Notation = Notation {
+    symbols: ["", "K", "M", "Md", "B", "Bd", "T", "Td", "Qa"],
+    words: [
+        "",
+        "thousand",
+        "million",
+        "milliard",
+        "billion",
+        "billiard",
+        "trillion",
+        "trilliard",
+        "quadrillion",
+    ],
+};
+
+/// Selects the kind of suffix used by [`Compact`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Style {
+    /// Short suffixes such as `1.2K`.
+    Symbols,
+    /// Spelled-out suffixes such as `1.2 thousand`.
+    Words,
+}
+
+/// Displays a number in compact notation.
+///
+/// The mantissa is displayed with `"{}"`, unless a precision is provided to
+/// the formatter, as in `"{:.1}"`.
+#[derive(Debug, Clone, Copy)]
+pub struct Compact<'a> {
+    value: Value,
+    notation: &'a Notation,
+    style: Style,
+}
+
+impl Compact<'static> {
+    /// Returns a `Compact` using the short scale symbols (`K`, `M`, `B`,
+    /// ...).
+    pub fn new<F>(x: F) -> Self
+    where
+        F: IntoF64,
+    {
+        Compact {
+            value: Value::new_with(x, Base::B1000, Constraint::UnitAndAbove),
+            notation: &SHORT_SCALE,
+            style: Style::Symbols,
+        }
+    }
+}
+
+impl<'a> Compact<'a> {
+    /// Returns self using the provided `notation`.
+    pub fn notation<'b>(self, notation: &'b Notation) -> Compact<'b> {
+        Compact {
+            value: self.value,
+            notation,
+            style: self.style,
+        }
+    }
+
+    /// Returns self using spelled-out suffixes, such as `million`.
+    pub fn words(self) -> Self {
+        Compact {
+            style: Style::Words,
+            ..self
+        }
+    }
+
+    /// Returns the underlying scaled value.
+    pub fn value(&self) -> &Value {
+        &self.value
+    }
+
+    /// Returns self scaled to the next suffix if formatting the mantissa
+    /// with `precision` rounds it up to 1000, which would otherwise display
+    /// `999.95K` as `1000.0K`.
+    fn rounded(&self, precision: usize) -> Self {
+        let mut digits = IntegralDigits::default();
+        let rounds_up = fmt::write(
+            &mut digits,
+            format_args!("{:.*}", precision, self.value.mantissa),
+        )
+        .is_ok()
+            && digits.count > 3;
+        match Prefix::from_exponent(self.value.prefix.exponent() + 3) {
+            Some(prefix) if rounds_up => Compact {
+                value: Value {
+                    mantissa: self.value.mantissa / 1000.0,
+                    prefix,
+                    ..self.value
+                },
+                ..*self
+            },
+            _ => *self,
+        }
+    }
+
+    /// Returns the suffix for the current value and style.
+    fn suffix(&self) -> &'a str {
+        let index = (self.value.prefix.exponent() / 3) as usize;
+        match self.style {
+            Style::Symbols => self.notation.symbols[index],
+            Style::Words => self.notation.words[index],
+        }
+    }
+}
+
+/// Counts the digits of the integral part of a formatted number.
+#[derive(Default)]
+struct IntegralDigits {
+    count: usize,
+    done: bool,
+}
+
+impl fmt::Write for IntegralDigits {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        for c in s.chars() {
+            match c {
+                '.' => self.done = true,
+                '0'..='9' if !self.done => self.count += 1,
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for Compact<'_> {
+    /// Writes the mantissa followed by the suffix. If the precision rounds
+    /// the mantissa up to 1000, the next suffix is used instead, as in
+    /// `1.0M` rather than `1000.0K`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let compact = match f.precision() {
+            Some(precision) => {
+                let compact = self.rounded(precision);
+                write!(f, "{:.*}", precision, compact.value.mantissa)?;
+                compact
+            }
+            None => {
+                write!(f, "{}", self.value.mantissa)?;
+                *self
+            }
+        };
+        let suffix = compact.suffix();
+        match self.style {
+            Style::Words if !suffix.is_empty() => write!(f, " {}", suffix),
+            _ => write!(f, "{}", suffix),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn short_scale_symbols() {
+        assert_eq!(format!("{}", Compact::new(0)), "0");
+        assert_eq!(format!("{}", Compact::new(0.5)), "0.5");
+        assert_eq!(format!("{}", Compact::new(999)), "999");
+        assert_eq!(format!("{}", Compact::new(1200)), "1.2K");
+        assert_eq!(format!("{:.1}", Compact::new(1234)), "1.2K");
+        assert_eq!(format!("{}", Compact::new(-3.4e6)), "-3.4M");
+        assert_eq!(format!("{}", Compact::new(5.6e9)), "5.6B");
+        assert_eq!(format!("{}", Compact::new(7e12)), "7T");
+        assert_eq!(format!("{}", Compact::new(2e24)), "2Sp");
+    }
+
+    #[test]
+    fn rounding_to_next_suffix() {
+        assert_eq!(format!("{:.1}", Compact::new(999_950.0)), "1.0M");
+        assert_eq!(format!("{:.1}", Compact::new(999_940.0)), "999.9K");
+        assert_eq!(format!("{:.0}", Compact::new(-999_500_000.0)), "-1B");
+        assert_eq!(format!("{:.0}", Compact::new(999.5)), "1K");
+        assert_eq!(format!("{}", Compact::new(999_950.0)), "999.95K");
+        assert_eq!(
+            format!("{:.2}", Compact::new(999_999.0).words()),
+            "1.00 million"
+        );
+        assert_eq!(format!("{:.0}", Compact::new(9_999.5e24)), "10000Sp");
+    }
+
+    #[test]
+    fn short_scale_words() {
+        assert_eq!(format!("{}", Compact::new(42).words()), "42");
+        assert_eq!(format!("{}", Compact::new(1.2e6).words()), "1.2 million");
+        assert_eq!(format!("{}", Compact::new(3e9).words()), "3 billion");
+        assert_eq!(
+            format!("{:.2}", Compact::new(4.5e12).words()),
+            "4.50 trillion"
+        );
+    }
+
+    #[test]
+    fn long_scale() {
+        let actual = format!("{}", Compact::new(3e9).notation(&LONG_SCALE));
+        assert_eq!(actual, "3Md");
+
+        let actual = format!("{}", Compact::new(3e9).notation(&LONG_SCALE).words());
+        assert_eq!(actual, "3 milliard");
+
+        let actual = format!("{}", Compact::new(2e12).words().notation(&LONG_SCALE));
+        assert_eq!(actual, "2 billion");
+    }
+
+    #[test]
+    fn custom_notation() {
+        const FRENCH: Notation = Notation {
+            symbols: ["", "k", "M", "Md", "Bn", "Bd", "Tn", "Td", "Qd"],
+            ..LONG_SCALE
+        };
+        let actual = format!("{}", Compact::new(1500).notation(&FRENCH));
+        assert_eq!(actual, "1.5k");
+    }
+}
@@ -0,0 +1,220 @@
+//! Arbitrary-precision decimal mantissas, with the `decimal` feature.
+//!
+//! [`Decimal`] implements [`Mantissa`], so that a
+//! [`Value`][`crate::value::Value`] built with
+//! [`Value::new_typed()`][`crate::value::Value::new_typed`] holds a decimal
+//! mantissa. In base `B1000` and in the myriad bases, whose prefixes are
+//! powers of ten, the prefix is selected from the number of decimal digits,
+//! and the mantissa is scaled by exact decimal exponent shifts:
+//! `1234.5678 kB` stays `1.2345678 MB`. Other bases go through `f64`.
+//!
+//! Values are formatted with [`format_value!()`][`crate::format_value`], and
+//! the digits of the mantissa can be grouped with
+//! [`separated_float()`][`crate::format::separated_float`]. Note that a
+//! precision such as `"{:.2}"` truncates a `Decimal` instead of rounding it,
+//! use [`Decimal::round_dp()`] first to round the mantissa.
+//!
+//! # Example
+//!
+//! ```
+//! use si_scale::decimal::Decimal;
+//! use si_scale::format_value;
+//! use si_scale::prelude::{Base, Constraint, Prefix, Value};
+//!
+//! let x: Decimal = "1234567.891".parse().unwrap();
+//! let value = Value::new_typed(x, Base::B1000, Constraint::None);
+//! assert_eq!(value.prefix, Prefix::Mega);
+//! assert_eq!(format!("{}B", format_value!(value, "{}")), "1.234567891 MB");
+//! assert_eq!(value.to_typed(), x);
+//!
+//! # #[cfg(feature = "alloc")]
+//! # {
+//! use si_scale::format::separated_float;
+//!
+//! let value = Value::new_typed(x, Base::B1000, Constraint::UnitOnly);
+//! let mantissa = separated_float(&format!("{}", value.mantissa), ' ');
+//! assert_eq!(mantissa, "1 234 567.891");
+//! # }
+//! ```
+
+use core::convert::TryFrom;
+
+use rust_decimal::prelude::ToPrimitive;
+pub use rust_decimal::Decimal;
+
+use crate::base::Base;
+use crate::value::Mantissa;
+
+impl Mantissa for Decimal {
+    fn to_f64(self) -> f64 {
+        ToPrimitive::to_f64(&self).unwrap_or(f64::NAN)
+    }
+
+    /// Returns self unchanged if `divisor` cannot be represented as a
+    /// `Decimal` or is zero.
+    fn div_f64(self, divisor: f64) -> Self {
+        Decimal::try_from(divisor)
+            .ok()
+            .and_then(|divisor| self.checked_div(divisor))
+            .map_or(self, |x| x.normalize())
+    }
+
+    /// Returns self unchanged if `factor` cannot be represented as a
+    /// `Decimal` or if the product overflows.
+    fn mul_f64(self, factor: f64) -> Self {
+        Decimal::try_from(factor)
+            .ok()
+            .and_then(|factor| self.checked_mul(factor))
+            .map_or(self, |x| x.normalize())
+    }
+
+    fn integral_exponent(self, base: &Base) -> i32 {
+        let digits_per_step = match base {
+            Base::B1000 => 3,
+            Base::Myriad(_) => 4,
+            _ => return base.integral_exponent_for(self.to_f64()),
+        };
+        if self.is_zero() {
+            return 0;
+        }
+        let log10 = self.mantissa().unsigned_abs().ilog10() as i32 - self.scale() as i32;
+        // Like `Base::integral_exponent_for()`, values below 1 万 are not
+        // scaled in the myriad bases.
+        if matches!(base, Base::Myriad(_)) && log10 < 4 {
+            return 0;
+        }
+        log10.div_euclid(digits_per_step) * 3
+    }
+
+    fn div_pow(self, base: &Base, exponent: i32) -> Self {
+        decimal_shift(base, exponent)
+            .and_then(|shift| shift_by(self, -shift))
+            .unwrap_or_else(|| self.div_f64(base.pow(exponent)))
+    }
+
+    fn mul_pow(self, base: &Base, exponent: i32) -> Self {
+        decimal_shift(base, exponent)
+            .and_then(|shift| shift_by(self, shift))
+            .unwrap_or_else(|| self.mul_f64(base.pow(exponent)))
+    }
+}
+
+/// Returns the power of ten equal to `base` raised to `exponent`, for the
+/// bases whose prefixes are powers of ten.
+fn decimal_shift(base: &Base, exponent: i32) -> Option<i32> {
+    match base {
+        Base::B1000 => Some(exponent),
+        Base::Myriad(_) => Some(exponent / 3 * 4),
+        _ => None,
+    }
+}
+
+/// Returns `x` multiplied by ten raised to `shift`, or `None` if the result
+/// does not fit in a `Decimal`.
+fn shift_by(x: Decimal, shift: i32) -> Option<Decimal> {
+    let factor = if shift < 0 {
+        let scale = shift.unsigned_abs();
+        if scale > Decimal::MAX_SCALE {
+            return None;
+        }
+        Decimal::new(1, scale)
+    } else {
+        let pow = 10i128.checked_pow(shift as u32)?;
+        Decimal::try_from_i128_with_scale(pow, 0).ok()?
+    };
+    x.checked_mul(factor).map(|x| x.normalize())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::base::MyriadSymbols;
+    #[cfg(feature = "alloc")]
+    use crate::format::{separated_float_with, Grouping};
+    #[cfg(feature = "alloc")]
+    use crate::format_value;
+    use crate::prefix::{Constraint, Prefix};
+    use crate::value::Value;
+
+    fn decimal(s: &str) -> Decimal {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn decimal_values() {
+        let cases = [
+            ("0", Prefix::Unit, "0"),
+            ("999.999", Prefix::Unit, "999.999"),
+            ("1000", Prefix::Kilo, "1"),
+            ("1234.5678", Prefix::Kilo, "1.2345678"),
+            ("0.1", Prefix::Milli, "100"),
+            ("0.000012300", Prefix::Micro, "12.3"),
+            ("-45000000.5", Prefix::Mega, "-45.0000005"),
+            (
+                "79228162514264337593543950335",
+                Prefix::Yotta,
+                "79228.162514264337593543950335",
+            ),
+        ];
+        for (x, prefix, mantissa) in cases {
+            let value = Value::new_typed(decimal(x), Base::B1000, Constraint::None);
+            assert_eq!(value.prefix, prefix, "{}", x);
+            assert_eq!(format!("{}", value.mantissa), mantissa, "{}", x);
+            assert_eq!(value.to_typed(), decimal(x).normalize(), "{}", x);
+        }
+
+        let value = Value::new_typed(decimal("1234.5"), Base::B1000, Constraint::UnitAndBelow);
+        assert_eq!(format!("{}", value), "1234.5");
+    }
+
+    #[test]
+    fn decimal_bases() {
+        let value = Value::new_typed(
+            decimal("123456789"),
+            Base::Myriad(MyriadSymbols::Simplified),
+            Constraint::None,
+        );
+        assert_eq!(format!("{}", value.mantissa), "1.23456789");
+        assert_eq!(value.to_typed(), decimal("123456789"));
+
+        let base = Base::Myriad(MyriadSymbols::Simplified);
+        for x in ["0.001", "-0.5", "9999.9"] {
+            let value = Value::new_typed(decimal(x), base, Constraint::None);
+            assert_eq!(value.prefix, Prefix::Unit, "{}", x);
+            assert_eq!(value.mantissa, decimal(x), "{}", x);
+            assert_eq!(
+                value.prefix,
+                Value::new_with(x.parse::<f64>().unwrap(), base, Constraint::None).prefix
+            );
+        }
+
+        let value = Value::new_typed(decimal("1536"), Base::B1024, Constraint::None);
+        assert_eq!(value.prefix, Prefix::Kilo);
+        assert_eq!(format!("{}", value.mantissa), "1.5");
+        assert_eq!(value.to_typed(), decimal("1536"));
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn format_decimal_values() {
+        let value = Value::new_typed(decimal("0.0000123456"), Base::B1000, Constraint::None);
+        assert_eq!(format!("{}s", format_value!(value, "{:.2}")), "12.34 µs");
+        let value = Value {
+            mantissa: value.mantissa.round_dp(2),
+            ..value
+        };
+        assert_eq!(format!("{}s", format_value!(value, "{}")), "12.35 µs");
+
+        let x = decimal("1234567.25");
+        let value = Value::new_typed(x, Base::B1000, Constraint::UnitOnly);
+        let mantissa = format!("{}", value.mantissa);
+        assert_eq!(
+            separated_float_with(&mantissa, ',', Grouping::Thousands),
+            "1,234,567.25"
+        );
+        assert_eq!(
+            separated_float_with(&mantissa, ',', Grouping::Indian),
+            "12,34,567.25"
+        );
+    }
+}
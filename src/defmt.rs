@@ -0,0 +1,391 @@
+//! Logging with [`defmt`](https://defmt.ferrous-systems.com), with the
+//! `defmt` feature.
+//!
+//! [`Value`], [`Prefix`] and [`Base`] implement `defmt::Format`, and the
+//! functions of this module are `defmt`-compatible versions of the
+//! predefined [helpers][`crate::helpers`]: instead of a `String`, they return
+//! a [`Scaled`] value which is formatted on the host, so that firmware only
+//! sends the mantissa and interned strings.
+//!
+//! Since `defmt` has no float precision, the mantissa of the helpers with a
+//! fixed number of decimals, such as [`seconds3()`], is rounded with integer
+//! arithmetic, like `"{:.3}"` with ties to even, and sent as integers.
+//! Mantissas above `1e15`, infinities and NaN are sent as `f64` instead.
+//!
+//! # Example
+//!
+//! ```
+//! use si_scale::defmt::{bytes1, seconds};
+//!
+//! // In firmware: defmt::info!("took {}", seconds(1.3e-5));
+//! assert_eq!(format!("{}", seconds(1.3e-5)), "13 µs");
+//! assert_eq!(format!("{}", bytes1(1_234_567)), "1.2 MB");
+//! ```
+
+use core::convert::TryFrom;
+use core::fmt;
+use core::time::Duration;
+
+use crate::base::Base;
+use crate::math;
+use crate::prefix::{Constraint, Prefix};
+use crate::value::{IntoF64, Mantissa, Value};
+
+impl ::defmt::Format for Prefix {
+    fn format(&self, f: ::defmt::Formatter<'_>) {
+        let symbol: &'static str = self.into();
+        ::defmt::write!(f, "{=str}", symbol)
+    }
+}
+
+impl ::defmt::Format for Base {
+    fn format(&self, f: ::defmt::Formatter<'_>) {
+        let name = match self {
+            Base::B1000 => "B1000",
+            Base::B1024 => "B1024",
+            Base::Indian => "Indian",
+            Base::Myriad(_) => "Myriad",
+            Base::Custom(_) => "Custom",
+        };
+        ::defmt::write!(f, "{=str}", name)
+    }
+}
+
+impl<T> ::defmt::Format for Value<T>
+where
+    T: Mantissa + ::defmt::Format,
+{
+    /// Formats the value like its `Display` implementation.
+    fn format(&self, f: ::defmt::Formatter<'_>) {
+        match self.base.prefix_symbol(self.prefix) {
+            "" => ::defmt::write!(f, "{}", self.mantissa),
+            symbol => ::defmt::write!(
+                f,
+                "{}{=str}{=str}",
+                self.mantissa,
+                self.base.separator(),
+                symbol
+            ),
+        }
+    }
+}
+
+/// A scaled value with its unit, returned by the helpers of this module,
+/// which formats like the predefined helpers with either `defmt` or
+/// `Display`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Scaled {
+    value: Value,
+    decimals: Option<u8>,
+    unit: &'static str,
+}
+
+impl Scaled {
+    /// Returns the scaled value, without its unit.
+    pub fn value(&self) -> Value {
+        self.value
+    }
+
+    /// Returns `"-"` if the mantissa is negative, including `-0.0` as with
+    /// `Display` for `f64`.
+    fn sign(&self) -> &'static str {
+        if self.value.mantissa.is_sign_negative() {
+            "-"
+        } else {
+            ""
+        }
+    }
+
+    /// Returns the integral and fractional parts of the magnitude of the
+    /// mantissa rounded to `decimals`, like `"{:.2}"`: from the exact binary
+    /// value, with ties to even. Returns `None` for mantissas which are not
+    /// finite or not below `1e15`.
+    fn decimal_parts(&self, decimals: u8) -> Option<(u64, u64)> {
+        let x = math::abs(self.value.mantissa);
+        if x.is_nan() || x >= 1e15 {
+            return None;
+        }
+        let factor = 10u64.pow(decimals.into());
+
+        // x = significand * 2^shift
+        let bits = x.to_bits();
+        let biased_exponent = (bits >> 52) as i32;
+        let fraction = bits & ((1 << 52) - 1);
+        let (significand, shift) = match biased_exponent {
+            0 => (fraction, -1074),
+            _ => (fraction | 1 << 52, biased_exponent - 1075),
+        };
+
+        let scaled = u128::from(significand) * u128::from(factor);
+        let rounded = if shift >= 0 {
+            scaled << shift
+        } else if shift > -128 {
+            let shift = shift.unsigned_abs();
+            let quotient = scaled >> shift;
+            let remainder = scaled & ((1 << shift) - 1);
+            let half = 1 << (shift - 1);
+            if remainder > half || (remainder == half && quotient % 2 == 1) {
+                quotient + 1
+            } else {
+                quotient
+            }
+        } else {
+            // `scaled` is below 2^63, so x is below half of the last decimal.
+            0
+        };
+        let rounded = u64::try_from(rounded).ok()?;
+        Some((rounded / factor, rounded % factor))
+    }
+}
+
+impl ::defmt::Format for Scaled {
+    fn format(&self, f: ::defmt::Formatter<'_>) {
+        let separator = self.value.base.separator();
+        let symbol = self.value.base.prefix_symbol(self.value.prefix);
+        let parts = self
+            .decimals
+            .and_then(|decimals| Some((decimals, self.decimal_parts(decimals)?)));
+        let (decimals, (integral, fraction)) = match parts {
+            Some(parts) => parts,
+            None => {
+                return ::defmt::write!(
+                    f,
+                    "{=f64}{=str}{=str}{=str}",
+                    self.value.mantissa,
+                    separator,
+                    symbol,
+                    self.unit
+                )
+            }
+        };
+        let sign = self.sign();
+        // Zero-padding hints must be literals.
+        match decimals {
+            0 => ::defmt::write!(f, "{=str}{=u64}", sign, integral),
+            1 => ::defmt::write!(f, "{=str}{=u64}.{=u64}", sign, integral, fraction),
+            2 => ::defmt::write!(f, "{=str}{=u64}.{=u64:02}", sign, integral, fraction),
+            _ => ::defmt::write!(f, "{=str}{=u64}.{=u64:03}", sign, integral, fraction),
+        }
+        ::defmt::write!(f, "{=str}{=str}{=str}", separator, symbol, self.unit)
+    }
+}
+
+impl fmt::Display for Scaled {
+    /// Writes the value as sent by its `defmt::Format` implementation, which
+    /// is also the output of the corresponding predefined helper, except for
+    /// the mantissas sent as `f64`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.decimals {
+            Some(decimals) => write!(f, "{:.*}", decimals.into(), self.value.mantissa)?,
+            None => write!(f, "{}", self.value.mantissa)?,
+        }
+        write!(
+            f,
+            "{}{}{}",
+            self.value.base.separator(),
+            self.value.base.prefix_symbol(self.value.prefix),
+            self.unit
+        )
+    }
+}
+
+macro_rules! defmt_fn {
+    (
+        $name:ident,
+        base: $base:ident,
+        constraint: $constraint:ident,
+        decimals: $decimals:expr,
+        unit: $unit:literal,
+        doc: $doc:literal
+    ) => {
+        #[doc = $doc]
+        pub fn $name<F>(x: F) -> Scaled
+        where
+            F: IntoF64,
+        {
+            Scaled {
+                value: Value::new_with(x, Base::$base, Constraint::$constraint),
+                decimals: $decimals,
+                unit: $unit,
+            }
+        }
+    };
+}
+
+defmt_fn!(seconds,
+          base: B1000,
+          constraint: UnitAndBelow,
+          decimals: None,
+          unit: "s",
+          doc: "Log a value in seconds.");
+
+defmt_fn!(seconds3,
+          base: B1000,
+          constraint: UnitAndBelow,
+          decimals: Some(3),
+          unit: "s",
+          doc: "Log a value in seconds with 3 decimals.");
+
+defmt_fn!(bytes,
+          base: B1000,
+          constraint: UnitAndAbove,
+          decimals: None,
+          unit: "B",
+          doc: "Log a value in bytes.");
+
+defmt_fn!(bytes1,
+          base: B1000,
+          constraint: UnitAndAbove,
+          decimals: Some(1),
+          unit: "B",
+          doc: "Log a value in bytes with 1 decimal.");
+
+defmt_fn!(bytes2,
+          base: B1000,
+          constraint: UnitAndAbove,
+          decimals: Some(2),
+          unit: "B",
+          doc: "Log a value in bytes with 2 decimals.");
+
+defmt_fn!(bibytes,
+          base: B1024,
+          constraint: UnitAndAbove,
+          decimals: None,
+          unit: "B",
+          doc: "Log a value in bibytes.");
+
+defmt_fn!(bibytes1,
+          base: B1024,
+          constraint: UnitAndAbove,
+          decimals: Some(1),
+          unit: "B",
+          doc: "Log a value in bibytes with 1 decimal.");
+
+defmt_fn!(bibytes2,
+          base: B1024,
+          constraint: UnitAndAbove,
+          decimals: Some(2),
+          unit: "B",
+          doc: "Log a value in bibytes with 2 decimals.");
+
+/// Log a duration in seconds, with the mantissa computed from its integer
+/// number of nanoseconds.
+pub fn duration(d: Duration) -> Scaled {
+    Scaled {
+        value: Value::from_duration(d, Constraint::UnitAndBelow),
+        decimals: None,
+        unit: "s",
+    }
+}
+
+/// Log a duration in seconds with 3 decimals.
+pub fn duration3(d: Duration) -> Scaled {
+    Scaled {
+        decimals: Some(3),
+        ..duration(d)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decimal_parts() {
+        assert_eq!(seconds3(1.3e-5).decimal_parts(3), Some((13, 0)));
+        assert_eq!(bytes2(1_234_567).decimal_parts(2), Some((1, 23)));
+        assert_eq!(bytes1(999_960).decimal_parts(1), Some((1000, 0)));
+        assert_eq!(seconds3(-0.0125).decimal_parts(3), Some((12, 500)));
+        assert_eq!(seconds3(-0.0125).decimal_parts(0), Some((12, 0)));
+        assert_eq!(seconds3(-0.0125).sign(), "-");
+
+        // Ties are rounded to even, like `"{:.1}"`.
+        assert_eq!(bytes1(1250).decimal_parts(1), Some((1, 2)));
+        assert_eq!(bytes1(1350).decimal_parts(1), Some((1, 4)));
+        assert_eq!(bytes2(1125).decimal_parts(2), Some((1, 12)));
+        assert_eq!(seconds3(1.0625).decimal_parts(3), Some((1, 62)));
+        assert_eq!(seconds3(2.5).decimal_parts(0), Some((2, 0)));
+        assert_eq!(seconds3(0.5).decimal_parts(0), Some((500, 0)));
+        // 0.15 is slightly below the tie.
+        assert_eq!(seconds3(0.15).decimal_parts(1), Some((150, 0)));
+        assert_eq!(bytes1(1150).decimal_parts(1), Some((1, 1)));
+
+        assert_eq!(seconds3(5e-324).decimal_parts(3), Some((0, 0)));
+        assert_eq!(seconds3(1e20).decimal_parts(3), None);
+        assert_eq!(seconds3(f64::NAN).decimal_parts(3), None);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn same_as_helpers() {
+        use crate::helpers;
+
+        // What the `defmt::Format` implementation sends, as a string.
+        let sent = |scaled: Scaled| -> String {
+            let mantissa = match scaled.decimals {
+                None => format!("{}", scaled.value.mantissa),
+                Some(decimals) => {
+                    let (integral, fraction) = scaled.decimal_parts(decimals).unwrap();
+                    match decimals {
+                        0 => format!("{}{}", scaled.sign(), integral),
+                        _ => format!(
+                            "{}{}.{:0width$}",
+                            scaled.sign(),
+                            integral,
+                            fraction,
+                            width = decimals.into()
+                        ),
+                    }
+                }
+            };
+            let value = scaled.value;
+            let prefix = value.base.prefix_symbol(value.prefix);
+            let sent = format!(
+                "{}{}{}{}",
+                mantissa,
+                value.base.separator(),
+                prefix,
+                scaled.unit
+            );
+            assert_eq!(sent, format!("{}", scaled));
+            sent
+        };
+
+        for x in [
+            0.0, 1.0, 1.3e-5, -1.25e-3, 0.1234567, 12.4e-7, 2.5, 1.0625, 1.0005, -0.0125,
+        ] {
+            assert_eq!(sent(seconds(x)), helpers::seconds(x), "{}", x);
+            assert_eq!(sent(seconds3(x)), helpers::seconds3(x), "{}", x);
+        }
+        // Includes ties such as 1.25 kB and 1.125 kB.
+        for x in [
+            0,
+            16,
+            999,
+            1_000,
+            1_234_567,
+            999_960,
+            3 << 30,
+            1250,
+            1350,
+            1125,
+            1150,
+            2560,
+        ] {
+            assert_eq!(sent(bytes(x)), helpers::bytes(x), "{}", x);
+            assert_eq!(sent(bytes1(x)), helpers::bytes1(x), "{}", x);
+            assert_eq!(sent(bytes2(x)), helpers::bytes2(x), "{}", x);
+            assert_eq!(sent(bibytes(x)), helpers::bibytes(x), "{}", x);
+            assert_eq!(sent(bibytes1(x)), helpers::bibytes1(x), "{}", x);
+            assert_eq!(sent(bibytes2(x)), helpers::bibytes2(x), "{}", x);
+        }
+        for d in [
+            Duration::from_nanos(1_234_567),
+            Duration::from_nanos(1_240),
+            Duration::from_millis(1_500),
+        ] {
+            assert_eq!(sent(duration(d)), helpers::duration(d));
+            assert_eq!(sent(duration3(d)), helpers::duration3(d));
+        }
+    }
+}
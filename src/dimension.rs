@@ -0,0 +1,301 @@
+//! Physical dimensions of units, tracked as exponents of base dimensions.
+//!
+//! A [`Dimension`] records the exponents of the SI base dimensions (mass,
+//! length, time, ...) plus information, counted either in bytes or in bits.
+//! Multiplying or dividing quantities adds or subtracts these exponents, so
+//! that bytes divided by seconds have the dimension of a throughput.
+//!
+//! # Example
+//!
+//! ```
+//! use si_scale::dimension::Dimension;
+//!
+//! assert_eq!(Dimension::ENERGY / Dimension::TIME, Dimension::POWER);
+//! assert_eq!(Dimension::NONE / Dimension::TIME, Dimension::FREQUENCY);
+//! assert_eq!(format!("{}", Dimension::BYTES / Dimension::TIME), "B/s");
+//! assert_eq!(format!("{}", Dimension::ENERGY), "kg·m²/s²");
+//! ```
+
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt;
+use core::ops::{Div, Mul};
+
+/// Number of base dimensions.
+const COUNT: usize = 9;
+
+/// Symbols of the coherent units of the base dimensions, in display order.
+const SYMBOLS: [&str; COUNT] = ["kg", "m", "s", "A", "K", "mol", "cd", "B", "bit"];
+
+/// Exponents of the base dimensions of a unit.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Dimension {
+    exponents: [i8; COUNT],
+}
+
+impl Dimension {
+    /// Dimensionless, as in a ratio or a count.
+    pub const NONE: Dimension = Dimension::from_exponents([0, 0, 0, 0, 0, 0, 0, 0, 0]);
+    /// Mass, in kilograms.
+    pub const MASS: Dimension = Dimension::from_exponents([1, 0, 0, 0, 0, 0, 0, 0, 0]);
+    /// Length, in meters.
+    pub const LENGTH: Dimension = Dimension::from_exponents([0, 1, 0, 0, 0, 0, 0, 0, 0]);
+    /// Time, in seconds.
+    pub const TIME: Dimension = Dimension::from_exponents([0, 0, 1, 0, 0, 0, 0, 0, 0]);
+    /// Electric current, in amperes.
+    pub const CURRENT: Dimension = Dimension::from_exponents([0, 0, 0, 1, 0, 0, 0, 0, 0]);
+    /// Temperature, in kelvins.
+    pub const TEMPERATURE: Dimension = Dimension::from_exponents([0, 0, 0, 0, 1, 0, 0, 0, 0]);
+    /// Amount of substance, in moles.
+    pub const AMOUNT: Dimension = Dimension::from_exponents([0, 0, 0, 0, 0, 1, 0, 0, 0]);
+    /// Luminous intensity, in candelas.
+    pub const LUMINOUS_INTENSITY: Dimension =
+        Dimension::from_exponents([0, 0, 0, 0, 0, 0, 1, 0, 0]);
+    /// Information, in bytes.
+    pub const BYTES: Dimension = Dimension::from_exponents([0, 0, 0, 0, 0, 0, 0, 1, 0]);
+    /// Information, in bits.
+    ///
+    /// Bits are kept apart from bytes: they are not converted into each
+    /// other.
+    pub const BITS: Dimension = Dimension::from_exponents([0, 0, 0, 0, 0, 0, 0, 0, 1]);
+
+    /// Frequency, in hertz (`s⁻¹`).
+    pub const FREQUENCY: Dimension = Dimension::from_exponents([0, 0, -1, 0, 0, 0, 0, 0, 0]);
+    /// Velocity (`m/s`).
+    pub const VELOCITY: Dimension = Dimension::from_exponents([0, 1, -1, 0, 0, 0, 0, 0, 0]);
+    /// Force, in newtons (`kg·m/s²`).
+    pub const FORCE: Dimension = Dimension::from_exponents([1, 1, -2, 0, 0, 0, 0, 0, 0]);
+    /// Energy, in joules (`kg·m²/s²`).
+    pub const ENERGY: Dimension = Dimension::from_exponents([1, 2, -2, 0, 0, 0, 0, 0, 0]);
+    /// Power, in watts (`kg·m²/s³`).
+    pub const POWER: Dimension = Dimension::from_exponents([1, 2, -3, 0, 0, 0, 0, 0, 0]);
+
+    const fn from_exponents(exponents: [i8; COUNT]) -> Self {
+        Dimension { exponents }
+    }
+
+    /// Returns `true` if all exponents are zero.
+    pub fn is_dimensionless(&self) -> bool {
+        *self == Self::NONE
+    }
+
+    /// Returns `true` if the dimension has information in its numerator,
+    /// such as bytes or bits per second.
+    pub fn has_information(&self) -> bool {
+        self.exponents[7] > 0 || self.exponents[8] > 0
+    }
+
+    /// Returns `true` if the dimension only has negative exponents, such as
+    /// `s⁻¹`.
+    pub fn is_reciprocal(&self) -> bool {
+        !self.is_dimensionless() && self.exponents.iter().all(|&exponent| exponent <= 0)
+    }
+
+    /// Returns `true` if a prefix in front of the composed symbol of the
+    /// dimension (see `Display`) scales it as a whole: its first unit is
+    /// neither raised to a power, nor the kilogram, as in `m/s` or `B/s`.
+    pub(crate) fn accepts_prefix(&self) -> bool {
+        match self.exponents.iter().position(|&exponent| exponent > 0) {
+            Some(0) | None => false,
+            Some(index) => self.exponents[index] == 1,
+        }
+    }
+
+    /// Returns the dimension raised to the power `n`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if an exponent overflows an `i8`, see
+    /// [`Dimension::checked_powi()`].
+    pub fn powi(self, n: i8) -> Self {
+        self.checked_powi(n).expect("dimension exponent overflow")
+    }
+
+    /// Returns the dimension raised to the power `n`, or `None` if an
+    /// exponent overflows an `i8`.
+    pub fn checked_powi(self, n: i8) -> Option<Self> {
+        let mut exponents = self.exponents;
+        for exponent in exponents.iter_mut() {
+            *exponent = exponent.checked_mul(n)?;
+        }
+        Some(Dimension { exponents })
+    }
+
+    /// Returns the dimension of the product of `self` and `rhs`, or `None`
+    /// if an exponent overflows an `i8`.
+    pub fn checked_mul(self, rhs: Dimension) -> Option<Self> {
+        let mut exponents = self.exponents;
+        for (exponent, rhs) in exponents.iter_mut().zip(rhs.exponents.iter()) {
+            *exponent = exponent.checked_add(*rhs)?;
+        }
+        Some(Dimension { exponents })
+    }
+
+    /// Returns the dimension of the quotient of `self` by `rhs`, or `None`
+    /// if an exponent overflows an `i8`.
+    pub fn checked_div(self, rhs: Dimension) -> Option<Self> {
+        let mut exponents = self.exponents;
+        for (exponent, rhs) in exponents.iter_mut().zip(rhs.exponents.iter()) {
+            *exponent = exponent.checked_sub(*rhs)?;
+        }
+        Some(Dimension { exponents })
+    }
+}
+
+impl Mul for Dimension {
+    type Output = Dimension;
+
+    /// # Panics
+    ///
+    /// Panics if an exponent overflows an `i8`, see
+    /// [`Dimension::checked_mul()`].
+    fn mul(self, rhs: Dimension) -> Self::Output {
+        self.checked_mul(rhs).expect("dimension exponent overflow")
+    }
+}
+
+impl Div for Dimension {
+    type Output = Dimension;
+
+    /// # Panics
+    ///
+    /// Panics if an exponent overflows an `i8`, see
+    /// [`Dimension::checked_div()`].
+    fn div(self, rhs: Dimension) -> Self::Output {
+        self.checked_div(rhs).expect("dimension exponent overflow")
+    }
+}
+
+/// Returns the superscript form of `exponent`, such as `²`, or an empty
+/// string for `1`.
+pub(crate) fn superscript(exponent: i32) -> String {
+    const DIGITS: [char; 10] = ['⁰', '¹', '²', '³', '⁴', '⁵', '⁶', '⁷', '⁸', '⁹'];
+    if exponent == 1 {
+        return String::new();
+    }
+    let sign = if exponent < 0 { "⁻" } else { "" };
+    let digits: String = exponent
+        .unsigned_abs()
+        .to_string()
+        .chars()
+        .map(|digit| DIGITS[digit.to_digit(10).unwrap_or(0) as usize])
+        .collect();
+    format!("{}{}", sign, digits)
+}
+
+impl fmt::Display for Dimension {
+    /// Writes the dimension as a composition of the coherent units of the
+    /// base dimensions, such as `B/s`, `kg·m²/s²` or `bit/(m·s)`, with
+    /// negative exponents such as `s⁻¹` if there is no numerator, and
+    /// nothing if dimensionless.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let terms = |positive: bool| -> Vec<String> {
+            SYMBOLS
+                .iter()
+                .zip(self.exponents.iter())
+                .filter(|(_, &exponent)| exponent != 0 && (exponent > 0) == positive)
+                .map(|(symbol, &exponent)| {
+                    format!("{}{}", symbol, superscript((exponent as i32).abs()))
+                })
+                .collect()
+        };
+        let numerator = terms(true);
+        let denominator = terms(false);
+
+        if numerator.is_empty() && denominator.is_empty() {
+            return Ok(());
+        }
+        if numerator.is_empty() {
+            let reciprocals: Vec<String> = SYMBOLS
+                .iter()
+                .zip(self.exponents.iter())
+                .filter(|(_, &exponent)| exponent != 0)
+                .map(|(symbol, &exponent)| format!("{}{}", symbol, superscript(exponent as i32)))
+                .collect();
+            return f.write_str(&reciprocals.join("·"));
+        }
+        f.write_str(&numerator.join("·"))?;
+        match denominator.len() {
+            0 => Ok(()),
+            1 => write!(f, "/{}", denominator[0]),
+            _ => write!(f, "/({})", denominator.join("·")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dimension_arithmetic() {
+        assert_eq!(Dimension::LENGTH / Dimension::TIME, Dimension::VELOCITY);
+        assert_eq!(
+            Dimension::MASS * Dimension::VELOCITY / Dimension::TIME,
+            Dimension::FORCE
+        );
+        assert_eq!(Dimension::FORCE * Dimension::LENGTH, Dimension::ENERGY);
+        assert_eq!(Dimension::POWER * Dimension::TIME, Dimension::ENERGY);
+        assert_eq!(Dimension::TIME.powi(-1), Dimension::FREQUENCY);
+        assert!((Dimension::BYTES / Dimension::BYTES).is_dimensionless());
+        assert_ne!(Dimension::BYTES, Dimension::BITS);
+        assert!((Dimension::BITS / Dimension::TIME).has_information());
+        assert!(!(Dimension::TIME / Dimension::BYTES).has_information());
+    }
+
+    #[test]
+    fn checked_dimension_arithmetic() {
+        let time = Dimension::TIME.checked_powi(127).unwrap();
+        assert_eq!(time.checked_mul(Dimension::TIME), None);
+        assert_eq!(time.checked_div(Dimension::FREQUENCY), None);
+        assert_eq!(time.checked_powi(2), None);
+        assert_eq!(
+            time.checked_div(Dimension::TIME),
+            Dimension::TIME.checked_powi(126)
+        );
+        assert_eq!(
+            Dimension::TIME.checked_powi(-128).unwrap().checked_powi(-1),
+            None
+        );
+    }
+
+    #[test]
+    fn display_dimensions() {
+        assert_eq!(format!("{}", Dimension::NONE), "");
+        assert_eq!(format!("{}", Dimension::TIME), "s");
+        assert_eq!(format!("{}", Dimension::FREQUENCY), "s⁻¹");
+        assert_eq!(
+            format!("{}", (Dimension::LENGTH * Dimension::TIME).powi(-2)),
+            "m⁻²·s⁻²"
+        );
+        assert!(Dimension::FREQUENCY.is_reciprocal());
+        assert!(!Dimension::VELOCITY.is_reciprocal());
+        assert!(!Dimension::NONE.is_reciprocal());
+        assert_eq!(format!("{}", Dimension::BYTES / Dimension::TIME), "B/s");
+        assert_eq!(format!("{}", Dimension::FORCE), "kg·m/s²");
+        assert_eq!(format!("{}", Dimension::LENGTH.powi(3)), "m³");
+        assert_eq!(
+            format!("{}", Dimension::BITS / Dimension::TIME / Dimension::LENGTH),
+            "bit/(m·s)"
+        );
+    }
+
+    #[test]
+    fn prefixes_of_composed_symbols() {
+        assert!(Dimension::VELOCITY.accepts_prefix());
+        assert!((Dimension::BYTES / Dimension::TIME).accepts_prefix());
+        assert!(!Dimension::FORCE.accepts_prefix());
+        assert!(!(Dimension::LENGTH.powi(2) / Dimension::TIME).accepts_prefix());
+        assert!(!Dimension::FREQUENCY.accepts_prefix());
+        assert!(!Dimension::NONE.accepts_prefix());
+    }
+
+    #[test]
+    fn superscripts() {
+        assert_eq!(superscript(1), "");
+        assert_eq!(superscript(2), "²");
+        assert_eq!(superscript(-1), "⁻¹");
+        assert_eq!(superscript(12), "¹²");
+    }
+}
@@ -0,0 +1,402 @@
+//! Integration with [`std::time::Duration`].
+//!
+//! Durations are formatted with the rules of the `seconds()` helpers by
+//! [`helpers::duration()`][`crate::helpers::duration()`] and
+//! [`helpers::duration3()`][`crate::helpers::duration3()`], and converted
+//! into a [`Quantity`][`crate::quantity::Quantity`] of seconds with `From`.
+//! In both cases, the mantissa is computed from the integer number of
+//! nanoseconds of the duration.
+//!
+//! Strings such as `"13 µs"` or `"1.5 ms"` are parsed into a `Duration` by
+//! [`parse_duration()`].
+//!
+//! # Example
+//!
+//! ```
+//! use std::time::Duration;
+//! use si_scale::duration::parse_duration;
+//! use si_scale::helpers::duration;
+//!
+//! assert_eq!(duration(Duration::from_nanos(1_234_567)), "1.234567 ms");
+//! assert_eq!(parse_duration("13 µs").unwrap(), Duration::from_micros(13));
+//! assert_eq!(parse_duration("1.5 ms").unwrap(), Duration::from_micros(1_500));
+//! ```
+//!
+//! Since kiloseconds are meaningless, [`HumanDuration`] displays durations
+//! of one second and above with days, hours, minutes and seconds, and
+//! shorter durations with SI prefixes. [`parse_human_duration()`] parses
+//! them back, as well as their compact form such as `"1h23m4.5s"`.
+//!
+//! ```
+//! use std::time::Duration;
+//! use si_scale::duration::{parse_human_duration, HumanDuration};
+//!
+//! let duration = Duration::from_millis(5_004_500);
+//! assert_eq!(format!("{}", HumanDuration::new(duration)), "1 h 23 min 24.5 s");
+//! assert_eq!(format!("{}", HumanDuration::new(Duration::from_micros(850))), "850 µs");
+//!
+//! assert_eq!(parse_human_duration("1h23m24.5s").unwrap(), duration);
+//! ```
+
+use alloc::format;
+use alloc::vec::Vec;
+use core::convert::TryFrom;
+use core::fmt;
+use core::str::FromStr;
+use core::time::Duration;
+
+use crate::base::Base;
+use crate::prefix::Prefix;
+use crate::quantity::{units, Quantity};
+use crate::{Result, SIUnitsError};
+
+/// Number of nanoseconds in a second.
+const NANOS_PER_SEC: u128 = 1_000_000_000;
+
+/// Parses a duration in seconds with an optional prefix, such as `"13 µs"`,
+/// `"1.5 ms"`, `"2 ks"` or `"0.25 s"`.
+///
+/// Decimal numbers are converted exactly, truncated to the nanosecond,
+/// whereas numbers in scientific notation go through `f64`.
+///
+/// # Errors
+///
+/// Returns [`SIUnitsError::UnitParsing`] if the string is not a number of
+/// (prefixed) seconds, or if the duration is negative or too large for a
+/// `Duration`.
+pub fn parse_duration(s: &str) -> Result<Duration> {
+    let error = || SIUnitsError::UnitParsing(format!("cannot parse {:?} as a duration", s));
+
+    let (number, prefix) = units::SECOND.split(s).ok_or_else(error)?;
+    // Exponent of the prefix relative to the nanosecond.
+    let exponent = prefix.exponent() + 9;
+
+    match decimal_nanos(&number, exponent) {
+        Some(nanos) => {
+            let secs = u64::try_from(nanos / NANOS_PER_SEC).map_err(|_| error())?;
+            Ok(Duration::new(secs, (nanos % NANOS_PER_SEC) as u32))
+        }
+        None => {
+            let mantissa: f64 = number.parse().map_err(|_| error())?;
+            let secs = mantissa * Base::B1000.pow(prefix.exponent());
+            Duration::try_from_secs_f64(secs).map_err(|_| error())
+        }
+    }
+}
+
+/// Returns the number of nanoseconds of the unsigned decimal `number`
+/// expressed in units of `10^exponent` nanoseconds, or `None` if `number`
+/// is not a plain decimal or overflows.
+fn decimal_nanos(number: &str, exponent: i32) -> Option<u128> {
+    let (integer, fraction) = number.split_once('.').unwrap_or((number, ""));
+    let is_decimal = |digits: &str| digits.chars().all(|c| c.is_ascii_digit());
+    if integer.len() + fraction.len() == 0 || !is_decimal(integer) || !is_decimal(fraction) {
+        return None;
+    }
+
+    let digits: u128 = format!("{}{}", integer, fraction).parse().ok()?;
+    let exponent = exponent - fraction.len() as i32;
+    if exponent >= 0 {
+        digits.checked_mul(10u128.checked_pow(exponent as u32)?)
+    } else {
+        Some(digits / 10u128.checked_pow(exponent.unsigned_abs())?)
+    }
+}
+
+/// Units of [`HumanDuration`], with their length in seconds, in descending
+/// order.
+const UNITS: [(&str, u64); 4] = [("d", 86_400), ("h", 3_600), ("min", 60), ("s", 1)];
+
+/// Selects how [`HumanDuration`] rounds its last component.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Rounding {
+    /// Rounds to the nearest value, halfway values away from zero.
+    #[default]
+    Nearest,
+    /// Rounds towards zero, truncating the remainder.
+    Down,
+    /// Rounds away from zero.
+    Up,
+}
+
+impl Rounding {
+    /// Returns `nanos` rounded to a multiple of `step`.
+    fn round(self, nanos: u128, step: u128) -> u128 {
+        let remainder = nanos % step;
+        let down = nanos - remainder;
+        match self {
+            Rounding::Down => down,
+            Rounding::Up if remainder > 0 => down + step,
+            Rounding::Up => down,
+            Rounding::Nearest if remainder * 2 >= step => down + step,
+            Rounding::Nearest => down,
+        }
+    }
+}
+
+/// Displays a duration with days (`d`), hours (`h`), minutes (`min`) and
+/// seconds (`s`) from one second, such as `1 h 23 min 4.5 s`, and with SI
+/// prefixes below, such as `850 ms` or `12 µs`.
+///
+/// At most [`HumanDuration::max_components()`] consecutive units are
+/// displayed, starting from the largest non-zero one, and components equal
+/// to zero are omitted. The last unit is rounded with the [`Rounding`] mode.
+/// When it is the second, its decimals are those of the duration, unless a
+/// precision is provided to the formatter, as in `"{:.1}"`.
+///
+/// # Example
+///
+/// ```
+/// use std::time::Duration;
+/// use si_scale::duration::{HumanDuration, Rounding};
+///
+/// let duration = Duration::from_millis(5_004_500);
+/// assert_eq!(format!("{}", HumanDuration::new(duration)), "1 h 23 min 24.5 s");
+/// assert_eq!(format!("{:.0}", HumanDuration::new(duration)), "1 h 23 min 25 s");
+///
+/// let duration = HumanDuration::new(duration).max_components(2);
+/// assert_eq!(format!("{}", duration), "1 h 23 min");
+/// assert_eq!(format!("{}", duration.rounding(Rounding::Up)), "1 h 24 min");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HumanDuration {
+    duration: Duration,
+    max_components: usize,
+    rounding: Rounding,
+}
+
+impl HumanDuration {
+    /// Returns a `HumanDuration` of `duration`, displaying at most 3
+    /// components, the last one rounded to the nearest value.
+    pub fn new(duration: Duration) -> Self {
+        HumanDuration {
+            duration,
+            max_components: 3,
+            rounding: Rounding::Nearest,
+        }
+    }
+
+    /// Returns self displaying at most `max_components` units, at least one.
+    pub fn max_components(self, max_components: usize) -> Self {
+        HumanDuration {
+            max_components: max_components.max(1),
+            ..self
+        }
+    }
+
+    /// Returns self rounding its last component with `rounding`.
+    pub fn rounding(self, rounding: Rounding) -> Self {
+        HumanDuration { rounding, ..self }
+    }
+
+    /// Returns the displayed duration.
+    pub fn duration(&self) -> Duration {
+        self.duration
+    }
+}
+
+impl fmt::Display for HumanDuration {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.duration < Duration::from_secs(1) {
+            return fmt::Display::fmt(&Quantity::from(self.duration), f);
+        }
+
+        let unit_nanos = |index: usize| u128::from(UNITS[index].1) * NANOS_PER_SEC;
+        let nanos = self.duration.as_nanos();
+        let seconds = UNITS.len() - 1;
+        let first = (0..seconds)
+            .find(|&index| nanos >= unit_nanos(index))
+            .unwrap_or(seconds);
+        let last = (first + self.max_components - 1).min(seconds);
+
+        let step = match f.precision() {
+            _ if last < seconds => unit_nanos(last),
+            Some(precision) if precision < 9 => 10u128.pow(9 - precision as u32),
+            _ => 1,
+        };
+        let mut rest = self.rounding.round(nanos, step);
+
+        let mut components = Vec::new();
+        for (index, (symbol, _)) in UNITS.iter().enumerate().take(last + 1) {
+            if index == seconds {
+                let seconds = rest as f64 / NANOS_PER_SEC as f64;
+                if rest > 0 || components.is_empty() {
+                    components.push(match f.precision() {
+                        Some(precision) => format!("{:.*} s", precision, seconds),
+                        None => format!("{} s", seconds),
+                    });
+                }
+                break;
+            }
+            let count = rest / unit_nanos(index);
+            rest %= unit_nanos(index);
+            if count > 0 {
+                components.push(format!("{} {}", count, symbol));
+            }
+        }
+
+        f.write_str(&components.join(" "))
+    }
+}
+
+/// Parses a duration made of components such as `"1 h 23 min 4.5 s"`,
+/// `"1h23m4.5s"` or `"850 ms"`, and returns their sum.
+///
+/// The units of the components are `d`, `h`, `min` (or `m`) and `s`, the
+/// latter optionally preceded by a prefix accepted by
+/// [`Prefix::from_str()`] or by `u` for `µ`, such as `ms`. Decimal numbers
+/// are converted exactly, truncated to the nanosecond.
+///
+/// # Errors
+///
+/// Returns [`SIUnitsError::UnitParsing`] if a component has no number or an
+/// unknown unit, or if the duration is too large for a `Duration`.
+pub fn parse_human_duration(s: &str) -> Result<Duration> {
+    let error = || SIUnitsError::UnitParsing(format!("cannot parse {:?} as a duration", s));
+
+    let mut rest = s.trim();
+    let mut nanos: u128 = 0;
+    if rest.is_empty() {
+        return Err(error());
+    }
+    while !rest.is_empty() {
+        let number_len = rest
+            .find(|c: char| !(c.is_ascii_digit() || c == '.' || c == '_'))
+            .unwrap_or(rest.len());
+        let (number, tail) = rest.split_at(number_len);
+        let tail = tail.trim_start();
+        let unit_len = tail
+            .find(|c: char| !c.is_alphabetic())
+            .unwrap_or(tail.len());
+        let (unit, tail) = tail.split_at(unit_len);
+        rest = tail.trim_start();
+
+        let number = number.replace('_', "");
+        let component = match unit {
+            _ if unit.is_empty() => return Err(error()),
+            "m" => decimal_nanos(&number, 9).and_then(|nanos| nanos.checked_mul(60)),
+            _ if !unit.ends_with('s') || unit == "s" => {
+                let (_, seconds) = UNITS
+                    .iter()
+                    .find(|(symbol, _)| *symbol == unit)
+                    .ok_or_else(error)?;
+                decimal_nanos(&number, 9).and_then(|nanos| nanos.checked_mul(u128::from(*seconds)))
+            }
+            _ => {
+                let prefix = unit.strip_suffix('s').ok_or_else(error)?;
+                let prefix = if prefix == "u" { "µ" } else { prefix };
+                let prefix = Prefix::from_str(prefix).map_err(|_| error())?;
+                decimal_nanos(&number, 9 + prefix.exponent())
+            }
+        };
+        nanos = component
+            .and_then(|component| nanos.checked_add(component))
+            .ok_or_else(error)?;
+    }
+
+    let secs = u64::try_from(nanos / NANOS_PER_SEC).map_err(|_| error())?;
+    Ok(Duration::new(secs, (nanos % NANOS_PER_SEC) as u32))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_durations() {
+        assert_eq!(parse_duration("13 µs").unwrap(), Duration::from_micros(13));
+        assert_eq!(parse_duration("13us").unwrap(), Duration::from_micros(13));
+        assert_eq!(
+            parse_duration("1.5 ms").unwrap(),
+            Duration::from_micros(1_500)
+        );
+        assert_eq!(parse_duration("0.1 s").unwrap(), Duration::from_millis(100));
+        assert_eq!(parse_duration("2 ks").unwrap(), Duration::from_secs(2_000));
+        assert_eq!(
+            parse_duration("1_000 ns").unwrap(),
+            Duration::from_micros(1)
+        );
+        assert_eq!(parse_duration("1.5 ps").unwrap(), Duration::ZERO);
+        assert_eq!(parse_duration(".5 s").unwrap(), Duration::from_millis(500));
+        assert_eq!(parse_duration("1e-3 s").unwrap(), Duration::from_millis(1));
+        assert_eq!(
+            parse_duration("1.000000001 s").unwrap(),
+            Duration::new(1, 1)
+        );
+    }
+
+    #[test]
+    fn parse_invalid_durations() {
+        assert!(parse_duration("13 µB").is_err());
+        assert!(parse_duration("s").is_err());
+        assert!(parse_duration("-1 s").is_err());
+        assert!(parse_duration("1e30 s").is_err());
+        assert!(parse_duration("100000000000000000000 s").is_err());
+    }
+
+    #[test]
+    fn display_human_durations() {
+        let display = |nanos: u64| format!("{}", HumanDuration::new(Duration::from_nanos(nanos)));
+        assert_eq!(display(0), "0 s");
+        assert_eq!(display(12_000), "12 µs");
+        assert_eq!(display(850_000_000), "850 ms");
+        assert_eq!(display(1_000_000_000), "1 s");
+        assert_eq!(display(59_250_000_000), "59.25 s");
+        assert_eq!(display(5_000_000_000_000), "1 h 23 min 20 s");
+        assert_eq!(display(3_600_000_000_000), "1 h");
+        assert_eq!(display(90_061_000_000_000), "1 d 1 h 1 min");
+        assert_eq!(display(3_604_500_000_000), "1 h 4.5 s");
+
+        let duration = HumanDuration::new(Duration::from_millis(5_004_500));
+        assert_eq!(format!("{:.2}", duration), "1 h 23 min 24.50 s");
+    }
+
+    #[test]
+    fn round_human_durations() {
+        let duration = HumanDuration::new(Duration::from_millis(3_599_600)).max_components(2);
+        assert_eq!(format!("{}", duration), "59 min 59.6 s");
+        assert_eq!(format!("{:.0}", duration), "1 h");
+        let duration = duration.rounding(Rounding::Down);
+        assert_eq!(format!("{:.0}", duration), "59 min 59 s");
+        assert_eq!(format!("{}", duration.max_components(1)), "59 min");
+
+        let duration = HumanDuration::new(Duration::from_secs(5_000)).max_components(1);
+        assert_eq!(format!("{}", duration), "1 h");
+        assert_eq!(format!("{}", duration.rounding(Rounding::Up)), "2 h");
+        assert_eq!(format!("{}", duration.max_components(0)), "1 h");
+    }
+
+    #[test]
+    fn parse_human_durations() {
+        let expected = Duration::from_millis(5_004_500);
+        assert_eq!(parse_human_duration("1 h 23 min 24.5 s").unwrap(), expected);
+        assert_eq!(parse_human_duration("1h23m24.5s").unwrap(), expected);
+        assert_eq!(
+            parse_human_duration("1.5 h").unwrap(),
+            Duration::from_secs(5_400)
+        );
+        assert_eq!(
+            parse_human_duration("2d").unwrap(),
+            Duration::from_secs(172_800)
+        );
+        assert_eq!(
+            parse_human_duration("850 ms").unwrap(),
+            Duration::from_millis(850)
+        );
+        assert_eq!(
+            parse_human_duration("1s 12us").unwrap(),
+            Duration::new(1, 12_000)
+        );
+        assert_eq!(
+            parse_human_duration("3 µs 5 ns").unwrap(),
+            Duration::from_nanos(3_005)
+        );
+
+        assert!(parse_human_duration("").is_err());
+        assert!(parse_human_duration("1 h 23").is_err());
+        assert!(parse_human_duration("h").is_err());
+        assert!(parse_human_duration("1 ks").is_ok());
+        assert!(parse_human_duration("1 w").is_err());
+        assert!(parse_human_duration("-1 s").is_err());
+    }
+}
@@ -0,0 +1,380 @@
+//! Exact scaling of integers.
+//!
+//! Converting integers through `f64` with [`IntoF64`][`crate::value::IntoF64`]
+//! loses precision beyond 2⁵³ and requires the `lossy-conversions` feature
+//! for `u64`, `i64`, `usize` and `isize`. An [`ExactValue`] instead selects
+//! the prefix and computes the mantissa of any integer with integer
+//! arithmetic, so that `u64::MAX` bytes are `18.446744073709551615 EB`.
+//!
+//! The [`ExactMantissa`] writes all the digits of the mantissa with `"{}"`,
+//! or rounds it half to even with a precision such as `"{:.2}"`, like the
+//! `f64` helpers, so that an
+//! `ExactValue` can be formatted with [`format_value!()`][`crate::format_value`].
+//!
+//! # Example
+//!
+//! ```
+//! use si_scale::exact::ExactValue;
+//! use si_scale::format_value;
+//! use si_scale::prelude::{Base, Constraint};
+//!
+//! # #[cfg(feature = "alloc")]
+//! # {
+//! use si_scale::helpers::{bibytes_exact, bytes_exact};
+//!
+//! assert_eq!(bytes_exact(u64::MAX), "18.446744073709551615 EB");
+//! assert_eq!(bibytes_exact(3u128 << 80), "3 YiB");
+//! # }
+//!
+//! let value = ExactValue::new_with(u64::MAX, Base::B1024, Constraint::UnitAndAbove);
+//! assert_eq!(format!("{}B", format_value!(value, "{:.3}")), "16.000 EiB");
+//! ```
+
+use core::fmt;
+
+use crate::base::Base;
+use crate::math;
+use crate::prefix::{Constraint, Prefix, PrefixSet};
+use crate::value::Value;
+
+/// Maximum number of fractional digits written. Without precision, it is only
+/// reached by custom bases whose ratio has prime factors other than 2 and 5.
+const MAX_FRACTION_DIGITS: usize = 128;
+
+/// A trait for integer types which are scaled exactly by [`ExactValue`].
+pub trait IntoInteger {
+    /// Returns whether self is negative, and its magnitude.
+    fn into_integer(self) -> (bool, u128);
+}
+
+macro_rules! impl_into_integer_unsigned {
+    ($($t:ty),*) => {
+        $(
+            impl IntoInteger for $t {
+                #[inline]
+                fn into_integer(self) -> (bool, u128) {
+                    (false, self as u128)
+                }
+            }
+        )*
+    };
+}
+
+macro_rules! impl_into_integer_signed {
+    ($($t:ty),*) => {
+        $(
+            impl IntoInteger for $t {
+                #[inline]
+                fn into_integer(self) -> (bool, u128) {
+                    (self < 0, self.unsigned_abs() as u128)
+                }
+            }
+        )*
+    };
+}
+
+impl_into_integer_unsigned!(u8, u16, u32, u64, u128, usize);
+impl_into_integer_signed!(i8, i16, i32, i64, i128, isize);
+
+/// The exact mantissa of an [`ExactValue`]: an integer divided by a power of
+/// the base.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExactMantissa {
+    negative: bool,
+    integral: u128,
+    remainder: u128,
+    divisor: u128,
+}
+
+impl ExactMantissa {
+    /// Returns `true` if the mantissa is strictly negative.
+    pub fn is_negative(&self) -> bool {
+        self.negative
+    }
+
+    /// Returns the magnitude of the integral part of the mantissa.
+    pub fn integral(&self) -> u128 {
+        self.integral
+    }
+
+    /// Returns the magnitude of the fractional part of the mantissa, as a
+    /// numerator and a denominator.
+    pub fn fraction(&self) -> (u128, u128) {
+        (self.remainder, self.divisor)
+    }
+
+    /// Converts the mantissa to a `f64`, which may lose precision.
+    pub fn to_f64(&self) -> f64 {
+        let magnitude = self.integral as f64 + self.remainder as f64 / self.divisor as f64;
+        if self.negative {
+            -magnitude
+        } else {
+            magnitude
+        }
+    }
+}
+
+impl fmt::Display for ExactMantissa {
+    /// Writes all the digits of the mantissa, or rounds it half to even to
+    /// the provided precision, as `f64` does. Width, fill, alignment and
+    /// sign flags are honored as for integers.
+    ///
+    /// At most 128 fractional digits are written: a larger precision is
+    /// clamped to 128.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut integral = self.integral;
+        let mut fraction = [0u8; MAX_FRACTION_DIGITS];
+        let mut fraction_len = 0;
+        let mut rest = self.remainder;
+
+        let len = f
+            .precision()
+            .unwrap_or(MAX_FRACTION_DIGITS)
+            .min(MAX_FRACTION_DIGITS);
+        while fraction_len < len && (rest > 0 || f.precision().is_some()) {
+            rest *= 10;
+            fraction[fraction_len] = (rest / self.divisor) as u8;
+            fraction_len += 1;
+            rest %= self.divisor;
+        }
+
+        // Rounds half to even: up above half, or at half if the last digit
+        // is odd. `rest` is compared with the complement to avoid overflows.
+        let last_digit = match fraction_len {
+            0 => (integral % 10) as u8,
+            _ => fraction[fraction_len - 1],
+        };
+        let round_up = match rest.cmp(&(self.divisor - rest)) {
+            core::cmp::Ordering::Greater => true,
+            core::cmp::Ordering::Equal => rest > 0 && last_digit % 2 == 1,
+            core::cmp::Ordering::Less => false,
+        };
+        if f.precision().is_some() && round_up {
+            let carry = fraction[..fraction_len].iter_mut().rev().all(|digit| {
+                *digit = (*digit + 1) % 10;
+                *digit == 0
+            });
+            if carry {
+                integral += 1;
+            }
+        }
+
+        // The 39 digits of a `u128`, the decimal point and the fraction.
+        let mut digits = [0u8; 40 + MAX_FRACTION_DIGITS];
+        let mut len = 0;
+        let mut push = |byte: u8| {
+            digits[len] = byte;
+            len += 1;
+        };
+        let integral_len = integral.checked_ilog10().unwrap_or(0) + 1;
+        for position in (0..integral_len).rev() {
+            push(b'0' + (integral / 10u128.pow(position) % 10) as u8);
+        }
+        if fraction_len > 0 {
+            push(b'.');
+            fraction[..fraction_len]
+                .iter()
+                .for_each(|digit| push(b'0' + digit));
+        }
+        let digits = core::str::from_utf8(&digits[..len]).map_err(|_| fmt::Error)?;
+        f.pad_integral(!self.negative, "", digits)
+    }
+}
+
+/// A value scaled exactly from an integer, see the [module
+/// documentation](self).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ExactValue {
+    /// Exact mantissa of the value after scaling.
+    pub mantissa: ExactMantissa,
+
+    /// Prefix indicating the scale.
+    pub prefix: Prefix,
+
+    /// Indicates the base, such as `1000` or `1024`.
+    pub base: Base,
+}
+
+impl ExactValue {
+    /// Returns an `ExactValue` for the default base `B1000`.
+    pub fn new<I>(x: I) -> Self
+    where
+        I: IntoInteger,
+    {
+        ExactValue::new_with(x, Base::B1000, Constraint::None)
+    }
+
+    /// Returns an `ExactValue` for the provided base, with the largest
+    /// prefix allowed by the constraint which is not above `x`, like
+    /// [`Value::new_with()`].
+    ///
+    /// Prefixes below the unit are never selected, nor prefixes whose
+    /// power of the base is not an integer, such as those of custom bases
+    /// with a fractional ratio.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use si_scale::exact::ExactValue;
+    /// use si_scale::prelude::{Base, Constraint, Prefix};
+    ///
+    /// let value = ExactValue::new_with(u64::MAX, Base::B1000, Constraint::UnitAndAbove);
+    /// assert_eq!(value.prefix, Prefix::Exa);
+    /// assert_eq!(value.mantissa.integral(), 18);
+    /// assert_eq!(format!("{}", value), "18.446744073709551615 E");
+    /// ```
+    pub fn new_with<I, C>(x: I, base: Base, prefix_constraint: C) -> Self
+    where
+        I: IntoInteger,
+        C: AsRef<Constraint>,
+    {
+        let (negative, magnitude) = x.into_integer();
+        let constraint = prefix_constraint.as_ref();
+
+        let (prefix, divisor) = PrefixSet::ALL
+            .iter()
+            .filter(|prefix| prefix.exponent() >= 0)
+            .filter(|&prefix| Value::closest_prefix_for(prefix.exponent(), constraint) == prefix)
+            .filter_map(|prefix| Some((prefix, integer_pow(&base, prefix.exponent())?)))
+            .filter(|&(_, divisor)| divisor <= magnitude)
+            .max_by_key(|&(prefix, _)| prefix)
+            .unwrap_or((Prefix::Unit, 1));
+
+        ExactValue {
+            mantissa: ExactMantissa {
+                negative: negative && magnitude > 0,
+                integral: magnitude / divisor,
+                remainder: magnitude % divisor,
+                divisor,
+            },
+            prefix,
+            base,
+        }
+    }
+
+    /// Converts self to a `Value`, whose mantissa may lose precision.
+    pub fn to_value(&self) -> Value {
+        Value {
+            mantissa: self.mantissa.to_f64(),
+            prefix: self.prefix,
+            base: self.base,
+        }
+    }
+}
+
+impl fmt::Display for ExactValue {
+    /// Writes the exact mantissa followed by the prefix symbol, like the
+    /// `Display` implementation of [`Value`]. Formatting options, such as
+    /// the precision, apply to the mantissa.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.mantissa, f)?;
+        match self.prefix {
+            Prefix::Unit => Ok(()),
+            _ => write!(
+                f,
+                "{}{}",
+                self.base.separator(),
+                self.base.prefix_symbol(self.prefix)
+            ),
+        }
+    }
+}
+
+/// Returns the base raised to the non-negative `exponent` as an integer, or
+/// `None` if it is not an integer or is too large for the mantissa to be
+/// computed.
+fn integer_pow(base: &Base, exponent: i32) -> Option<u128> {
+    let step = (exponent / 3) as u32;
+    let pow = match base {
+        Base::B1000 => 1000u128.checked_pow(step),
+        Base::B1024 => 1024u128.checked_pow(step),
+        Base::Indian if step == 0 => Some(1),
+        Base::Indian => 10u128.checked_pow(2 * step + 3),
+        Base::Myriad(_) => 10_000u128.checked_pow(step),
+        Base::Custom(scale) => {
+            let ratio = scale.ratio();
+            let is_integer = math::fract(ratio) == 0.0 && (1.0..=u64::MAX as f64).contains(&ratio);
+            if step as i32 > scale.max_step() || !is_integer {
+                None
+            } else {
+                (ratio as u128).checked_pow(step)
+            }
+        }
+    };
+    pow.filter(|&pow| pow <= u128::MAX / 10)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn exact_values() {
+        let value = ExactValue::new(u64::MAX);
+        assert_eq!(value.prefix, Prefix::Exa);
+        assert_eq!(format!("{}", value), "18.446744073709551615 E");
+        assert_eq!(format!("{:.2}", value.mantissa), "18.45");
+        assert_eq!(value.to_value().mantissa, u64::MAX as f64 / 1e18);
+
+        assert_eq!(format!("{}", ExactValue::new(0u8)), "0");
+        assert_eq!(format!("{}", ExactValue::new(999i16)), "999");
+        assert_eq!(format!("{}", ExactValue::new(-1_500i64)), "-1.5 k");
+        assert_eq!(
+            format!("{}", ExactValue::new(i128::MIN)),
+            "-170141183460469.231731687303715884105728 Y"
+        );
+        assert_eq!(
+            format!("{}", ExactValue::new(u128::MAX)),
+            "340282366920938.463463374607431768211455 Y"
+        );
+    }
+
+    #[test]
+    fn exact_bases_and_constraints() {
+        let value = ExactValue::new_with(u64::MAX, Base::B1024, Constraint::None);
+        assert_eq!(format!("{:.0}", value), "16 Ei");
+        assert_eq!(value.mantissa.fraction(), ((1 << 60) - 1, 1 << 60));
+
+        let value = ExactValue::new_with(1_234_567usize, Base::B1000, Constraint::UnitOnly);
+        assert_eq!(format!("{}", value), "1234567");
+
+        let value = ExactValue::new_with(12_345_678u32, Base::Indian, Constraint::None);
+        assert_eq!(format!("{}", value), "1.2345678 Cr");
+
+        let constraint = Constraint::Set(PrefixSet::single(Prefix::Milli).with(Prefix::Kilo));
+        assert_eq!(
+            format!("{}", ExactValue::new_with(12, Base::B1000, &constraint)),
+            "12"
+        );
+        assert_eq!(
+            format!(
+                "{}",
+                ExactValue::new_with(12_000_000, Base::B1000, &constraint)
+            ),
+            "12000 k"
+        );
+    }
+
+    #[test]
+    fn round_exact_mantissas() {
+        let mantissa = |x: u64| ExactValue::new(x).mantissa;
+        assert_eq!(format!("{:.1}", mantissa(1_250)), "1.2");
+        assert_eq!(format!("{:.1}", mantissa(1_350)), "1.4");
+        assert_eq!(format!("{:.1}", mantissa(1_251)), "1.3");
+        assert_eq!(format!("{:.1}", mantissa(1_249)), "1.2");
+        assert_eq!(format!("{:.2}", mantissa(999_999)), "1000.00");
+        assert_eq!(format!("{:.0}", mantissa(1_500)), "2");
+        assert_eq!(format!("{:.0}", mantissa(2_500)), "2");
+        assert_eq!(format!("{:.2}", mantissa(1_995)), "2.00");
+        assert_eq!(format!("{:.3}", mantissa(1_500)), "1.500");
+        assert_eq!(format!("{:>8.1}", mantissa(1_500)), "     1.5");
+        assert_eq!(format!("{:<6}|", mantissa(1_500)), "1.5   |");
+        assert_eq!(format!("{:+}", mantissa(1_500)), "+1.5");
+        assert_eq!(format!("{:.1}", ExactValue::new(-1_250).mantissa), "-1.2");
+        assert_eq!(format!("{:.1}", ExactValue::new(-1_350).mantissa), "-1.4");
+        assert_eq!(
+            format!("{:.130}", mantissa(1_500)),
+            format!("1.5{}", "0".repeat(127))
+        );
+    }
+}
@@ -0,0 +1,375 @@
+//! Fixed-point values for targets without a floating-point unit.
+//!
+//! A [`FixedValue`] scales an integer reading, such as microvolts in an
+//! `i32`, in base `B1000` without any float operation: the prefix is selected
+//! by counting the decimal digits of the integer, and the value is stored as
+//! an integer mantissa with a decimal scale. Formatting uses integer
+//! arithmetic only, either through `Display` or into a caller-provided byte
+//! buffer with [`FixedValue::write_to()`].
+//!
+//! # Example
+//!
+//! ```
+//! use si_scale::fixed::FixedValue;
+//! use si_scale::prelude::{Constraint, Prefix};
+//!
+//! // 1_234_567 µV
+//! let value = FixedValue::new(1_234_567i32, Prefix::Micro);
+//! assert_eq!(value.prefix, Prefix::Unit);
+//! assert_eq!(value.scale, 6);
+//!
+//! let mut buf = [0u8; 32];
+//! assert_eq!(value.write_to(&mut buf).unwrap(), "1.234567");
+//! assert_eq!(format!("{:.2}V", value), "1.23V");
+//!
+//! let value = FixedValue::new_with(-4_500i32, Prefix::Micro, Constraint::UnitAndBelow);
+//! assert_eq!(format!("{}V", value), "-4.5 mV");
+//! ```
+
+use core::convert::TryFrom;
+use core::fmt::{self, Write};
+
+use crate::base::Base;
+use crate::buffer::SliceWriter;
+use crate::math;
+use crate::prefix::{Constraint, Prefix, PrefixSet};
+use crate::value::Value;
+
+/// Maximum precision honored when formatting a `FixedValue`, larger
+/// precisions are reduced to it.
+pub const MAX_PRECISION: usize = 64;
+
+/// Capacity of the digits buffer: the 39 digits of a `u128`, the trailing
+/// zeros of the largest negative scale, a decimal point and the fractional
+/// digits.
+const DIGITS_CAPACITY: usize = 39 + 48 + 1 + MAX_PRECISION;
+
+/// A value scaled with integer arithmetic only, see the [module
+/// documentation](self).
+///
+/// The scaled number is `mantissa / 10^scale` followed by the prefix; a
+/// negative scale means trailing zeros, which happens when the constraint
+/// requires a prefix below the unit of the input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FixedValue {
+    /// Integer mantissa, before applying the decimal scale.
+    pub mantissa: i64,
+
+    /// Number of decimal digits of the mantissa after the decimal point.
+    pub scale: i32,
+
+    /// Prefix indicating the scale, in base `B1000`.
+    pub prefix: Prefix,
+
+    /// Prefixes allowed by the constraint, into which rounding can carry,
+    /// as in `999.9999 m` rounded to `1.000` instead of `1000.000 m`.
+    pub allowed: PrefixSet,
+}
+
+impl FixedValue {
+    /// Returns a `FixedValue` for `x` expressed in the `unit` prefix, such
+    /// as `Prefix::Micro` for microvolts, with any prefix.
+    pub fn new<I>(x: I, unit: Prefix) -> Self
+    where
+        I: Into<i64>,
+    {
+        FixedValue::new_with(x, unit, Constraint::None)
+    }
+
+    /// Returns a `FixedValue` for `x` expressed in the `unit` prefix, with
+    /// the largest prefix allowed by the constraint which is not above the
+    /// value, like [`Value::new_with()`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use si_scale::fixed::FixedValue;
+    /// use si_scale::prelude::{Constraint, Prefix};
+    ///
+    /// let value = FixedValue::new_with(12_345i32, Prefix::Milli, Constraint::UnitAndAbove);
+    /// assert_eq!(value.prefix, Prefix::Unit);
+    /// assert_eq!((value.mantissa, value.scale), (12_345, 3));
+    ///
+    /// let value = FixedValue::new_with(12i32, Prefix::Kilo, Constraint::UnitOnly);
+    /// assert_eq!((value.mantissa, value.scale), (12, -3));
+    /// assert_eq!(format!("{}", value), "12000");
+    /// ```
+    pub fn new_with<I, C>(x: I, unit: Prefix, prefix_constraint: C) -> Self
+    where
+        I: Into<i64>,
+        C: AsRef<Constraint>,
+    {
+        let mantissa = x.into();
+        let exponent = match mantissa.unsigned_abs().checked_ilog10() {
+            Some(digits) => (unit.exponent() + digits as i32).div_euclid(3) * 3,
+            None => 0,
+        };
+        let allowed = prefix_constraint.as_ref().to_set();
+        let prefix = Value::closest_prefix_for(exponent, prefix_constraint);
+
+        FixedValue {
+            mantissa,
+            scale: prefix.exponent() - unit.exponent(),
+            prefix,
+            allowed,
+        }
+    }
+
+    /// Converts self to a `Value`, which uses float arithmetic.
+    pub fn to_value(&self) -> Value {
+        let mantissa = if self.scale >= 0 {
+            self.mantissa as f64 / math::powi(10f64, self.scale)
+        } else {
+            self.mantissa as f64 * math::powi(10f64, -self.scale)
+        };
+        Value {
+            mantissa,
+            prefix: self.prefix,
+            base: Base::B1000,
+        }
+    }
+
+    /// Writes self as with `"{}"` into `buf`, and returns the written
+    /// string, or `fmt::Error` if `buf` is too small.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use si_scale::fixed::FixedValue;
+    /// use si_scale::prelude::Prefix;
+    ///
+    /// let value = FixedValue::new(13_000i32, Prefix::Nano);
+    /// let mut buf = [0u8; 8];
+    /// assert_eq!(value.write_to(&mut buf).unwrap(), "13 µ");
+    /// assert!(value.write_to(&mut buf[..4]).is_err());
+    /// ```
+    pub fn write_to<'b>(&self, buf: &'b mut [u8]) -> Result<&'b str, fmt::Error> {
+        let mut writer = SliceWriter::new(buf);
+        write!(writer, "{}", self)?;
+        Ok(writer.into_str())
+    }
+
+    /// Returns the next allowed prefix if rounding self to `precision`
+    /// carries its integral part from below 1000 up to 1000, as in
+    /// `999.999 m` rounded to `1000.00 m`.
+    fn carried_prefix(&self, precision: usize) -> Option<Prefix> {
+        let precision = precision.min(MAX_PRECISION);
+        let scale = usize::try_from(self.scale).ok()?;
+        if precision >= scale {
+            return None;
+        }
+        let number = u128::from(self.mantissa.unsigned_abs());
+        if number / 10u128.checked_pow(scale as u32)? >= 1000 {
+            return None;
+        }
+        let rounded = round_division(number, 10u128.pow((scale - precision) as u32));
+        if rounded / 10u128.pow(precision as u32) < 1000 {
+            return None;
+        }
+        let exponent = self.prefix.exponent() + 3;
+        let next = Value::closest_prefix_for(exponent, Constraint::Set(self.allowed));
+        (next.exponent() == exponent).then_some(next)
+    }
+
+    /// Writes the scaled number into `digits`, rounded half away from zero
+    /// to the precision if any, or without trailing fractional zeros.
+    fn write_digits(&self, digits: &mut SliceWriter<'_>, precision: Option<usize>) -> fmt::Result {
+        let precision = precision.map(|precision| precision.min(MAX_PRECISION));
+        let mut number = u128::from(self.mantissa.unsigned_abs());
+        let mut fraction_len = self.scale.max(0) as usize;
+        let integral_zeros = (-self.scale).max(0) as usize;
+
+        match precision {
+            None => {
+                while fraction_len > 0 && number % 10 == 0 {
+                    number /= 10;
+                    fraction_len -= 1;
+                }
+            }
+            Some(precision) if precision < fraction_len => {
+                let dropped = (fraction_len - precision) as u32;
+                number = match 10u128.checked_pow(dropped) {
+                    Some(divisor) => round_division(number, divisor),
+                    None => 0,
+                };
+                fraction_len = precision;
+            }
+            Some(_) => {}
+        }
+        let fraction_zeros = precision.map_or(0, |precision| precision - fraction_len);
+
+        let mut number_digits = [0u8; 39];
+        let mut number_digits = SliceWriter::new(&mut number_digits);
+        write!(number_digits, "{}", number)?;
+        let number_digits = number_digits.as_str();
+        let integral_len = number_digits.len().saturating_sub(fraction_len);
+
+        if integral_len == 0 {
+            digits.write_char('0')?;
+        } else {
+            digits.write_str(&number_digits[..integral_len])?;
+        }
+        digits.write_repeated('0', integral_zeros)?;
+        if fraction_len + fraction_zeros > 0 {
+            digits.write_char('.')?;
+            digits.write_repeated('0', fraction_len.saturating_sub(number_digits.len()))?;
+            digits.write_str(&number_digits[integral_len..])?;
+            digits.write_repeated('0', fraction_zeros)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for FixedValue {
+    /// Writes the scaled number followed by the prefix symbol, like the
+    /// `Display` implementation of [`Value`]. The precision rounds the
+    /// number half away from zero, carrying into the next allowed prefix
+    /// if it rounds up to 1000, and width, fill, alignment and sign flags
+    /// are honored as for integers.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(prefix) = f
+            .precision()
+            .and_then(|precision| self.carried_prefix(precision))
+        {
+            // The rounded number is exactly 1000 times the next prefix.
+            let carried = FixedValue {
+                mantissa: self.mantissa.signum(),
+                scale: 0,
+                prefix,
+                allowed: self.allowed,
+            };
+            return fmt::Display::fmt(&carried, f);
+        }
+
+        let mut digits = [0u8; DIGITS_CAPACITY];
+        let mut digits = SliceWriter::new(&mut digits);
+        self.write_digits(&mut digits, f.precision())?;
+        f.pad_integral(self.mantissa >= 0, "", digits.as_str())?;
+
+        match self.prefix {
+            Prefix::Unit => Ok(()),
+            _ => write!(
+                f,
+                "{}{}",
+                Base::B1000.separator(),
+                Base::B1000.prefix_symbol(self.prefix)
+            ),
+        }
+    }
+}
+
+/// Returns `number / divisor` rounded half away from zero.
+fn round_division(number: u128, divisor: u128) -> u128 {
+    if number % divisor >= divisor - number % divisor {
+        number / divisor + 1
+    } else {
+        number / divisor
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prefix::PrefixSet;
+
+    #[test]
+    fn fixed_values() {
+        let cases = [
+            (0i64, Prefix::Micro, Prefix::Unit, "0"),
+            (7, Prefix::Micro, Prefix::Micro, "7 µ"),
+            (999, Prefix::Micro, Prefix::Micro, "999 µ"),
+            (1_000, Prefix::Micro, Prefix::Milli, "1 m"),
+            (1_234_567, Prefix::Micro, Prefix::Unit, "1.234567"),
+            (-12_300, Prefix::Nano, Prefix::Micro, "-12.3 µ"),
+            (5, Prefix::Unit, Prefix::Unit, "5"),
+            (
+                i64::MAX,
+                Prefix::Unit,
+                Prefix::Exa,
+                "9.223372036854775807 E",
+            ),
+            (
+                i64::MIN,
+                Prefix::Yocto,
+                Prefix::Micro,
+                "-9.223372036854775808 µ",
+            ),
+            (12, Prefix::Yotta, Prefix::Yotta, "12 Y"),
+        ];
+        for (x, unit, prefix, expected) in cases {
+            let value = FixedValue::new(x, unit);
+            assert_eq!(value.prefix, prefix, "{}", x);
+            assert_eq!(format!("{}", value), expected, "{}", x);
+        }
+
+        let value = FixedValue::new(1_500i32, Prefix::Milli);
+        assert_eq!(value.to_value(), Value::new(1.5));
+    }
+
+    #[test]
+    fn fixed_constraints() {
+        let value = FixedValue::new_with(5u8, Prefix::Nano, Constraint::UnitAndAbove);
+        assert_eq!(format!("{}", value), "0.000000005");
+        assert_eq!(format!("{:.3}", value), "0.000");
+
+        let value = FixedValue::new_with(-25i16, Prefix::Mega, Constraint::UnitAndBelow);
+        assert_eq!(format!("{}", value), "-25000000");
+        assert_eq!(format!("{:.1}", value), "-25000000.0");
+
+        let constraint = Constraint::Set(PrefixSet::single(Prefix::Milli).with(Prefix::Kilo));
+        let value = FixedValue::new_with(12_345_678i32, Prefix::Micro, &constraint);
+        assert_eq!(format!("{}", value), "12345.678 m");
+
+        // Rounding does not carry into a prefix which is not allowed.
+        let value = FixedValue::new_with(999_999i32, Prefix::Micro, &constraint);
+        assert_eq!(format!("{:.2}", value), "1000.00 m");
+        let value = FixedValue::new_with(999_999i32, Prefix::Micro, Constraint::UnitAndBelow);
+        assert_eq!(format!("{:.2}", value), "1.00");
+    }
+
+    #[test]
+    fn format_fixed_values() {
+        let value = FixedValue::new(1_234_567i32, Prefix::Micro);
+        assert_eq!(format!("{:.0}", value), "1");
+        assert_eq!(format!("{:.2}", value), "1.23");
+        assert_eq!(format!("{:.5}", value), "1.23457");
+        assert_eq!(format!("{:.8}", value), "1.23456700");
+        assert_eq!(format!("{:>8.3}|", value), "   1.235|");
+        assert_eq!(format!("{:<6.1}|", value), "1.2   |");
+        assert_eq!(format!("{:+.1}", value), "+1.2");
+        assert_eq!(
+            format!("{:.1}", FixedValue::new(-1_250, Prefix::Milli)),
+            "-1.3"
+        );
+        assert_eq!(
+            format!("{:.2}", FixedValue::new(999_999, Prefix::Micro)),
+            "1.00"
+        );
+        assert_eq!(
+            format!("{:.1}", FixedValue::new(-999_960, Prefix::Nano)),
+            "-1.0 m"
+        );
+        assert_eq!(
+            format!("{:>8.3}|", FixedValue::new(999_999_900, Prefix::Micro)),
+            "   1.000 k|"
+        );
+        assert_eq!(
+            format!("{:.3}", FixedValue::new(999_999, Prefix::Micro)),
+            "999.999 m"
+        );
+        assert_eq!(
+            format!("{:.1}", FixedValue::new(95, Prefix::Micro)),
+            "95.0 µ"
+        );
+    }
+
+    #[test]
+    fn write_fixed_values_to_buffers() {
+        let value = FixedValue::new(-12_300i32, Prefix::Nano);
+        let mut buf = [0u8; 16];
+        assert_eq!(value.write_to(&mut buf), Ok("-12.3 µ"));
+        assert_eq!(value.write_to(&mut buf[..8]), Ok("-12.3 µ"));
+        assert_eq!(value.write_to(&mut buf[..7]), Err(fmt::Error));
+        assert_eq!(value.write_to(&mut buf[..3]), Err(fmt::Error));
+    }
+}
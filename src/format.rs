@@ -1,8 +1,12 @@
 //! The `format_value` macro.
 
+#[cfg(feature = "alloc")]
+use alloc::string::String;
+use core::fmt;
+
 /// Formats a [`Value`][`crate::value::Value`]'s mantissa and unit prefix (but
 /// not the unit itself). Because it simply delegates to
-/// [`format_args!()`][`std::format_args`], the output should be consumed by
+/// [`format_args!()`][`core::format_args`], the output should be consumed by
 /// macros such as `println!()`, `write!()`, etc.
 ///
 /// It provides more control than the `Display` implementation in
@@ -33,7 +37,8 @@
 /// assert_eq!(actual, expected);
 /// ```
 ///
-/// Additionally, you can provide a symbol for thousands' groupings.
+/// Additionally, with the `alloc` feature, you can provide a symbol for
+/// thousands' groupings.
 ///
 /// # Example
 ///
@@ -44,6 +49,9 @@
 ///
 /// ```
 /// use si_scale::format_value;
+/// # #[cfg(not(feature = "alloc"))]
+/// # fn main() {}
+/// # #[cfg(feature = "alloc")]
 /// # fn main() {
 /// use si_scale::{value::Value, base::Base, prefix::Constraint};
 ///
@@ -65,50 +73,101 @@
 macro_rules! format_value {
     ($name:ident, $fmt_str:literal) => {
         format_args! {
-            concat!($fmt_str, " {}{}"),
+            concat!($fmt_str, "{}{}"),
             $name.mantissa,
-            $name.prefix,
-            match $name.base {
-                $crate::base::Base::B1000 => "",
-                $crate::base::Base::B1024 => if $name.prefix == $crate::prefix::Prefix::Unit {""} else {"i"},
-            },
+            $name.base.separator(),
+            $name.base.prefix_symbol($name.prefix),
         }
     };
 
     ($name:ident, $fmt_str:literal, groupings: $separator:expr) => {
         format_args! {
-            "{} {}{}",
-            $crate::format::separated_float(&format!($fmt_str, $name.mantissa), $separator),
-            $name.prefix,
-            match $name.base {
-                $crate::base::Base::B1000 => "",
-                $crate::base::Base::B1024 => if $name.prefix == $crate::prefix::Prefix::Unit {""} else {"i"},
-            },
+            "{}{}{}",
+            $crate::format::separated_float_with(
+                &$crate::__private::format!($fmt_str, $name.mantissa),
+                $separator,
+                $name.base.grouping(),
+            ),
+            $name.base.separator(),
+            $name.base.prefix_symbol($name.prefix),
         }
     };
 
     ($name:ident, $fmt_str:literal, groupings: $separator:expr, no_unit) => {
         format_args! {
-            "{}{}{}{}",
-            $crate::format::separated_float(&format!($fmt_str, $name.mantissa), $separator),
-            match $name.prefix {
-                $crate::prefix::Prefix::Unit => "",
-                _=> " "
-            },
-            $name.prefix,
-            match $name.base {
-                $crate::base::Base::B1000 => "",
-                $crate::base::Base::B1024 => if $name.prefix == $crate::prefix::Prefix::Unit {""} else {"i"},
+            "{}{}{}",
+            $crate::format::separated_float_with(
+                &$crate::__private::format!($fmt_str, $name.mantissa),
+                $separator,
+                $name.base.grouping(),
+            ),
+            if $name.base.prefix_symbol($name.prefix).is_empty() {
+                ""
+            } else {
+                $name.base.separator()
             },
+            $name.base.prefix_symbol($name.prefix),
         }
     };
 }
 
+/// Specifies how digits are grouped by [`separated_float_with()`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Grouping {
+    /// Groups of 3 digits, as in `1_234_567.123_4`.
+    Thousands,
+    /// Groups of 3 digits for the first group left of the decimal point, then
+    /// groups of 2 digits, as in `12,34,567`. The fractional part uses groups
+    /// of 3 digits.
+    Indian,
+    /// Groups of 4 digits, as in `1234,5678.1234`, used with the East Asian
+    /// myriad scale.
+    Myriad,
+}
+
+impl Grouping {
+    /// Returns `true` if a separator is inserted before the integral digit
+    /// at position `pos`, counted from the right and starting at 0.
+    fn is_integral_boundary(&self, pos: i32) -> bool {
+        match self {
+            Self::Thousands => pos > 1 && pos % 3 == 0,
+            Self::Indian => pos == 3 || (pos > 3 && (pos - 3) % 2 == 0),
+            Self::Myriad => pos > 1 && pos % 4 == 0,
+        }
+    }
+
+    /// Returns the size of the digit groups in the fractional part.
+    fn fractional_group_size(&self) -> i32 {
+        match self {
+            Self::Thousands | Self::Indian => 3,
+            Self::Myriad => 4,
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
 /// Given a input `&str` representing a digit (float or int), this function
 /// returns a `String` in which thousands separators are inserted both on the
 /// integral part and the fractional part.
 ///
 pub fn separated_float(input: &str, separator: char) -> String {
+    separated_float_with(input, separator, Grouping::Thousands)
+}
+
+#[cfg(feature = "alloc")]
+/// Same as [`separated_float()`], but groups the digits according to the
+/// provided `grouping`.
+///
+/// # Example
+///
+/// ```
+/// use si_scale::format::{separated_float_with, Grouping};
+///
+/// let actual = separated_float_with("1234567.891", ',', Grouping::Indian);
+/// assert_eq!(actual, "12,34,567.891");
+/// ```
+///
+pub fn separated_float_with(input: &str, separator: char, grouping: Grouping) -> String {
     let idx = match input.find('.') {
         Some(i) => i,
         None => input.len(),
@@ -117,18 +176,73 @@ pub fn separated_float(input: &str, separator: char) -> String {
     let int_part = &input[..idx];
     let frac_part = &input[idx..];
 
-    let int_part_separated = separate_thousands_backward(int_part, separator);
-    let frac_part_separated = separate_thousands_forward(frac_part, separator);
+    let int_part_separated = separate_backward(int_part, separator, grouping);
+    let frac_part_separated = separate_forward(frac_part, separator, grouping);
     int_part_separated + &frac_part_separated
 }
 
-fn separate_thousands_backward(input: &str, separator: char) -> String {
-    let mut output = String::with_capacity(input.len() + input.len() / 4);
+/// Same as [`separated_float_with()`], but writes into the `fmt::Write` sink
+/// `w` instead of allocating a `String`.
+///
+/// # Example
+///
+/// ```
+/// use si_scale::buffer::SliceWriter;
+/// use si_scale::format::{write_separated_float, Grouping};
+///
+/// let mut buf = [0u8; 16];
+/// let mut writer = SliceWriter::new(&mut buf);
+/// write_separated_float(&mut writer, "1234567.891", ',', Grouping::Indian).unwrap();
+/// assert_eq!(writer.as_str(), "12,34,567.891");
+/// ```
+///
+pub fn write_separated_float<W: fmt::Write>(
+    w: &mut W,
+    input: &str,
+    separator: char,
+    grouping: Grouping,
+) -> fmt::Result {
+    let idx = match input.find('.') {
+        Some(i) => i,
+        None => input.len(),
+    };
+    let (int_part, frac_part) = input.split_at(idx);
+
+    // Same as `separate_backward()`: a separator follows each integral digit
+    // at a group boundary, counted from the right.
+    let mut pos = int_part.chars().filter(char::is_ascii_digit).count() as i32;
+    for ch in int_part.chars() {
+        w.write_char(ch)?;
+        if ch.is_ascii_digit() {
+            pos -= 1;
+            if grouping.is_integral_boundary(pos) {
+                w.write_char(separator)?;
+            }
+        }
+    }
+
+    let size = grouping.fractional_group_size();
+    let mut pos = 0;
+    for ch in frac_part.chars() {
+        if ch.is_ascii_digit() {
+            if pos > 1 && pos % size == 0 {
+                w.write_char(separator)?;
+            }
+            pos += 1;
+        }
+        w.write_char(ch)?;
+    }
+    Ok(())
+}
+
+#[cfg(feature = "alloc")]
+fn separate_backward(input: &str, separator: char, grouping: Grouping) -> String {
+    let mut output = String::with_capacity(input.len() + input.len() / 2);
     let mut pos = 0;
     for ch in input.chars().rev() {
         if ch.is_ascii_digit() {
             // don't push a sep on first char
-            if pos > 1 && pos % 3 == 0 {
+            if grouping.is_integral_boundary(pos) {
                 output.push(separator);
             }
             pos += 1;
@@ -138,13 +252,15 @@ fn separate_thousands_backward(input: &str, separator: char) -> String {
     output.chars().rev().collect()
 }
 
-fn separate_thousands_forward(input: &str, separator: char) -> String {
-    let mut output = String::with_capacity(input.len() + input.len() / 4);
+#[cfg(feature = "alloc")]
+fn separate_forward(input: &str, separator: char, grouping: Grouping) -> String {
+    let size = grouping.fractional_group_size();
+    let mut output = String::with_capacity(input.len() + input.len() / size as usize);
     let mut pos = 0;
     for ch in input.chars() {
         if ch.is_ascii_digit() {
             // don't push a sep on first char
-            if pos > 1 && pos % 3 == 0 {
+            if pos > 1 && pos % size == 0 {
                 output.push(separator);
             }
             pos += 1;
@@ -156,6 +272,7 @@ fn separate_thousands_forward(input: &str, separator: char) -> String {
 
 #[cfg(test)]
 mod tests {
+    #[cfg(feature = "alloc")]
     use super::*;
     use crate::value::Value;
 
@@ -175,6 +292,7 @@ mod tests {
     }
 
     #[test]
+    #[cfg(feature = "alloc")]
     fn format_value_with_groupings() {
         let x = 1234.5678;
         let v: Value = x.into();
@@ -204,6 +322,7 @@ mod tests {
     }
 
     #[test]
+    #[cfg(feature = "alloc")]
     fn separate_float() {
         let actual: String = separated_float("123456.123456", '_');
         let expected = "123_456.123_456";
@@ -223,27 +342,136 @@ mod tests {
     }
 
     #[test]
+    #[cfg(feature = "alloc")]
     fn int_part_with_separate_thousands_backward() {
-        let actual = separate_thousands_backward("123456", '_');
+        let actual = separate_backward("123456", '_', Grouping::Thousands);
         let expected = "123_456";
         assert_eq!(actual, expected);
 
-        let actual = separate_thousands_backward("  123456..", '_');
+        let actual = separate_backward("  123456..", '_', Grouping::Thousands);
         let expected = "  123_456..";
         assert_eq!(actual, expected);
     }
 
     #[test]
+    #[cfg(feature = "alloc")]
     fn frac_part_with_separate_thousands_forward() {
-        let actual = separate_thousands_forward(".123456789", '_');
+        let actual = separate_forward(".123456789", '_', Grouping::Thousands);
         let expected = ".123_456_789";
         assert_eq!(actual, expected);
 
-        let actual = separate_thousands_forward(".1234567--", '_');
+        let actual = separate_forward(".1234567--", '_', Grouping::Thousands);
         let expected = ".123_456_7--";
         assert_eq!(actual, expected);
     }
 
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn indian_grouping() {
+        let actual = separated_float_with("1234567", ',', Grouping::Indian);
+        let expected = "12,34,567";
+        assert_eq!(actual, expected);
+
+        let actual = separated_float_with("-123456789.123456", ',', Grouping::Indian);
+        let expected = "-12,34,56,789.123,456";
+        assert_eq!(actual, expected);
+
+        let actual = separated_float_with("12345", ',', Grouping::Indian);
+        let expected = "12,345";
+        assert_eq!(actual, expected);
+
+        let actual = separated_float_with("123", ',', Grouping::Indian);
+        let expected = "123";
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn format_indian_value() {
+        use crate::base::Base;
+        use crate::prefix::Constraint;
+
+        let v = Value::new_with(1_234_567, Base::Indian, Constraint::None);
+        let actual = format!("{}", format_value!(v, "{:.1}"));
+        assert_eq!(actual, "12.3 L");
+
+        let v = Value::new_with(45_000_000, Base::Indian, Constraint::None);
+        let actual = format!("{}", format_value!(v, "{}"));
+        assert_eq!(actual, "4.5 Cr");
+
+        let v = Value::new_with(1_234_567, Base::Indian, Constraint::UnitOnly);
+        let actual = format!("{}", format_value!(v, "{}", groupings: ',', no_unit));
+        assert_eq!(actual, "12,34,567");
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn myriad_grouping() {
+        let actual = separated_float_with("123456789.123456", ',', Grouping::Myriad);
+        let expected = "1,2345,6789.1234,56";
+        assert_eq!(actual, expected);
+
+        let actual = separated_float_with("1234", ',', Grouping::Myriad);
+        let expected = "1234";
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn format_myriad_value() {
+        use crate::base::{Base, MyriadSymbols};
+        use crate::prefix::Constraint;
+
+        let base = Base::Myriad(MyriadSymbols::Simplified);
+        let v = Value::new_with(12_000, base, Constraint::None);
+        let actual = format!("{}人", format_value!(v, "{}"));
+        assert_eq!(actual, "1.2万人");
+
+        let base = Base::Myriad(MyriadSymbols::Japanese);
+        let v = Value::new_with(340_000_000, base, Constraint::None);
+        let actual = format!("{}円", format_value!(v, "{:.1}"));
+        assert_eq!(actual, "3.4億円");
+
+        let v = Value::new_with(12_345_678, base, Constraint::UnitOnly);
+        let actual = format!("{}", format_value!(v, "{}", groupings: ',', no_unit));
+        assert_eq!(actual, "1234,5678");
+    }
+
+    #[test]
+    fn write_separated_floats() {
+        use crate::buffer::SliceWriter;
+        use crate::format::{write_separated_float, Grouping};
+
+        let cases = [
+            ("123456.123456", Grouping::Thousands, "123_456.123_456"),
+            (
+                "1234567.1234567",
+                Grouping::Thousands,
+                "1_234_567.123_456_7",
+            ),
+            (
+                "--1234567.1234567++",
+                Grouping::Thousands,
+                "--1_234_567.123_456_7++",
+            ),
+            (
+                "-123456789.123456",
+                Grouping::Indian,
+                "-12_34_56_789.123_456",
+            ),
+            ("123", Grouping::Indian, "123"),
+            ("123456789.123456", Grouping::Myriad, "1_2345_6789.1234_56"),
+        ];
+        for (input, grouping, expected) in cases {
+            let mut buf = [0u8; 32];
+            let mut writer = SliceWriter::new(&mut buf);
+            write_separated_float(&mut writer, input, '_', grouping).unwrap();
+            assert_eq!(writer.as_str(), expected);
+            #[cfg(feature = "alloc")]
+            assert_eq!(separated_float_with(input, '_', grouping), expected);
+        }
+    }
+
     #[test]
     fn format_zero_value() {
         let x = 0.0f32;
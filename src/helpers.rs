@@ -66,30 +66,63 @@
 //! assert_eq!(actual, expected);
 //! ```
 
+use alloc::format;
+use alloc::string::String;
+
 /// Three nearly identical variants: with the unit argument only, with unit and
 /// groupings arguments, with groupings argument only. If you happen to know how
 /// to factor this, please make a suggestion!
+///
+/// Each variant accepts an optional `strategy: <expr>` argument after the
+/// constraint, selecting the prefix with a
+/// [`Strategy`][`crate::strategy::Strategy`] instead of the default
+/// [`Floor`][`crate::strategy::Floor`].
+///
+/// A fourth variant takes a predefined [`Unit`][`crate::quantity::Unit`]
+/// instead of the base, constraint and unit arguments, and also accepts an
+/// optional `strategy: <expr>` argument after the unit:
+///
+/// ```
+/// use si_scale::scale_fn;
+///
+/// scale_fn!(meters,
+///           unit: si_scale::quantity::units::METER,
+///           mantissa_fmt: "{:.1}",
+///           doc: "Print a value in meters with 1 decimal.");
+///
+/// assert_eq!(meters(1234), "1.2 km");
+///
+/// scale_fn!(bytes_early,
+///           unit: si_scale::quantity::units::BYTE,
+///           strategy: si_scale::strategy::Threshold(0.9),
+///           mantissa_fmt: "{:.2}",
+///           doc: "Print a value in bytes, switching to the next prefix at 0.9.");
+///
+/// assert_eq!(bytes_early(950_000), "0.95 MB");
+/// ```
 #[macro_export]
 macro_rules! scale_fn {
     (
         $name:ident,
         base: $base_arg:ident,
         constraint: $constraint_arg:ident,
+        $(strategy: $strategy:expr,)?
         mantissa_fmt: $mantissa_fmt:expr,
         unit: $unit_arg:literal,
         doc: $doc_arg:literal
     ) => {
         #[doc=$doc_arg]
-        pub fn $name<F>(x: F) -> String
+        pub fn $name<F>(x: F) -> $crate::__private::String
         where
             F: $crate::value::IntoF64,
         {
-            let value = $crate::value::Value::new_with(
+            let value = $crate::value::Value::new_with_strategy(
                 x,
                 $crate::base::Base::$base_arg,
                 $crate::prefix::Constraint::$constraint_arg,
+                $crate::scale_fn!(@strategy $($strategy)?),
             );
-            format!(
+            $crate::__private::format!(
                 "{}{}",
                 $crate::format_value!(value, $mantissa_fmt),
                 $unit_arg
@@ -101,22 +134,24 @@ macro_rules! scale_fn {
         $name:ident,
         base: $base_arg:ident,
         constraint: $constraint_arg:ident,
+        $(strategy: $strategy:expr,)?
         mantissa_fmt: $mantissa_fmt:expr,
         groupings: $sep_arg:literal,
         unit: $unit_arg:literal,
         doc: $doc_arg:literal
     ) => {
         #[doc=$doc_arg]
-        pub fn $name<F>(x: F) -> String
+        pub fn $name<F>(x: F) -> $crate::__private::String
         where
             F: $crate::value::IntoF64,
         {
-            let value = $crate::value::Value::new_with(
+            let value = $crate::value::Value::new_with_strategy(
                 x,
                 $crate::base::Base::$base_arg,
                 $crate::prefix::Constraint::$constraint_arg,
+                $crate::scale_fn!(@strategy $($strategy)?),
             );
-            format!(
+            $crate::__private::format!(
                 "{}{}",
                 $crate::format_value!(value, $mantissa_fmt, groupings: $sep_arg),
                 $unit_arg
@@ -128,26 +163,66 @@ macro_rules! scale_fn {
         $name:ident,
         base: $base_arg:ident,
         constraint: $constraint_arg:ident,
+        $(strategy: $strategy:expr,)?
         mantissa_fmt: $mantissa_fmt:expr,
         groupings: $sep_arg:literal,
         doc: $doc_arg:literal
     ) => {
         #[doc=$doc_arg]
-        pub fn $name<F>(x: F) -> String
+        pub fn $name<F>(x: F) -> $crate::__private::String
         where
             F: $crate::value::IntoF64,
         {
-            let value = $crate::value::Value::new_with(
+            let value = $crate::value::Value::new_with_strategy(
                 x,
                 $crate::base::Base::$base_arg,
                 $crate::prefix::Constraint::$constraint_arg,
+                $crate::scale_fn!(@strategy $($strategy)?),
             );
-            format!(
+            $crate::__private::format!(
                 "{}",
                 $crate::format_value!(value, $mantissa_fmt, groupings: $sep_arg, no_unit)
             )
         }
     };
+
+    (
+        $name:ident,
+        unit: $unit_arg:path,
+        $(strategy: $strategy:expr,)?
+        mantissa_fmt: $mantissa_fmt:expr,
+        doc: $doc_arg:literal
+    ) => {
+        #[doc=$doc_arg]
+        pub fn $name<F>(x: F) -> $crate::__private::String
+        where
+            F: $crate::value::IntoF64,
+        {
+            let quantity = $crate::scale_fn!(@quantity x, $unit_arg $(, $strategy)?);
+            let value = quantity.value;
+            $crate::__private::format!(
+                "{}{}",
+                $crate::format_value!(value, $mantissa_fmt),
+                quantity.unit.full_symbol()
+            )
+        }
+    };
+
+    (@quantity $x:ident, $unit_arg:path) => {
+        $crate::quantity::Quantity::new($x, $unit_arg)
+    };
+
+    (@quantity $x:ident, $unit_arg:path, $strategy:expr) => {
+        $crate::quantity::Quantity::new_with_strategy($x, $unit_arg, $strategy)
+    };
+
+    (@strategy) => {
+        $crate::strategy::Floor
+    };
+
+    (@strategy $strategy:expr) => {
+        $strategy
+    };
 }
 
 scale_fn!(number_,
@@ -158,24 +233,41 @@ scale_fn!(number_,
           doc: "Print a number without units.");
 
 scale_fn!(seconds,
-          base: B1000,
-          constraint: UnitAndBelow,
+          unit: crate::quantity::units::SECOND,
           mantissa_fmt: "{}",
-          unit: "s",
           doc: "Print a value in seconds.");
 
 scale_fn!(seconds3,
-          base: B1000,
-          constraint: UnitAndBelow,
+          unit: crate::quantity::units::SECOND,
           mantissa_fmt: "{:.3}",
-          unit: "s",
           doc: "Print a value in seconds with 3 decimals.");
 
+/// Print a duration in seconds, like [`seconds()`], with the mantissa computed
+/// from its integer number of nanoseconds.
+pub fn duration(d: core::time::Duration) -> String {
+    let quantity = crate::quantity::Quantity::from(d);
+    let value = quantity.value;
+    format!(
+        "{}{}",
+        crate::format_value!(value, "{}"),
+        quantity.unit.full_symbol()
+    )
+}
+
+/// Print a duration in seconds with 3 decimals, like [`seconds3()`].
+pub fn duration3(d: core::time::Duration) -> String {
+    let quantity = crate::quantity::Quantity::from(d);
+    let value = quantity.value;
+    format!(
+        "{}{}",
+        crate::format_value!(value, "{:.3}"),
+        quantity.unit.full_symbol()
+    )
+}
+
 scale_fn!(bytes,
-          base: B1000,
-          constraint: UnitAndAbove,
+          unit: crate::quantity::units::BYTE,
           mantissa_fmt: "{}",
-          unit: "B",
           doc: "Print a value in bytes.");
 
 scale_fn!(bytes_,
@@ -187,40 +279,56 @@ scale_fn!(bytes_,
           doc: "Print a value in bytes with thousands separator.");
 
 scale_fn!(bytes1,
-          base: B1000,
-          constraint: UnitAndAbove,
+          unit: crate::quantity::units::BYTE,
           mantissa_fmt: "{:.1}",
-          unit: "B",
           doc: "Print a value in bytes with 1 decimal.");
 
 scale_fn!(bytes2,
-          base: B1000,
-          constraint: UnitAndAbove,
+          unit: crate::quantity::units::BYTE,
           mantissa_fmt: "{:.2}",
-          unit: "B",
           doc: "Print a value in bytes with 2 decimals.");
 
 scale_fn!(bibytes,
-          base: B1024,
-          constraint: UnitAndAbove,
+          unit: crate::quantity::units::BIBYTE,
           mantissa_fmt: "{}",
-          unit: "B",
           doc: "Print a value in bibytes.");
 
 scale_fn!(bibytes1,
-          base: B1024,
-          constraint: UnitAndAbove,
+          unit: crate::quantity::units::BIBYTE,
           mantissa_fmt: "{:.1}",
-          unit: "B",
           doc: "Print a value in bibytes with 1 decimal.");
 
 scale_fn!(bibytes2,
-          base: B1024,
-          constraint: UnitAndAbove,
+          unit: crate::quantity::units::BIBYTE,
           mantissa_fmt: "{:.2}",
-          unit: "B",
           doc: "Print a value in bibytes with 2 decimals.");
 
+/// Print an integer in bytes, with the mantissa computed exactly.
+pub fn bytes_exact<I>(x: I) -> String
+where
+    I: crate::exact::IntoInteger,
+{
+    let value = crate::exact::ExactValue::new_with(
+        x,
+        crate::base::Base::B1000,
+        crate::prefix::Constraint::UnitAndAbove,
+    );
+    format!("{}B", crate::format_value!(value, "{}"))
+}
+
+/// Print an integer in bibytes, with the mantissa computed exactly.
+pub fn bibytes_exact<I>(x: I) -> String
+where
+    I: crate::exact::IntoInteger,
+{
+    let value = crate::exact::ExactValue::new_with(
+        x,
+        crate::base::Base::B1024,
+        crate::prefix::Constraint::UnitAndAbove,
+    );
+    format!("{}B", crate::format_value!(value, "{}"))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -263,6 +371,42 @@ mod tests {
         assert_eq!(actual, expected);
     }
 
+    #[test]
+    fn test_exact_bytes() {
+        let actual = format!("result is {}", bytes_exact(u64::MAX));
+        let expected = "result is 18.446744073709551615 EB";
+        assert_eq!(actual, expected);
+
+        let actual = format!("result is {}", bytes_exact(999u16));
+        let expected = "result is 999 B";
+        assert_eq!(actual, expected);
+
+        let actual = format!("result is {}", bibytes_exact(1_536i64));
+        let expected = "result is 1.5 kiB";
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_durations() {
+        use core::time::Duration;
+
+        let actual = format!("result is {}", duration(Duration::from_nanos(1_234_567)));
+        let expected = "result is 1.234567 ms";
+        assert_eq!(actual, expected);
+
+        let actual = format!("result is {}", duration(Duration::from_millis(5_400_000)));
+        let expected = "result is 5400 s";
+        assert_eq!(actual, expected);
+
+        let actual = format!("result is {:>10}", duration3(Duration::from_nanos(1_240)));
+        let expected = "result is   1.240 µs";
+        assert_eq!(actual, expected);
+
+        let actual = format!("result is {}", seconds(Duration::from_millis(1_500)));
+        let expected = "result is 1.5 s";
+        assert_eq!(actual, expected);
+    }
+
     #[test]
     fn test_seconds3() {
         let actual = format!("result is {}", seconds3(1234.5678));
@@ -376,6 +520,39 @@ mod tests {
         assert_eq!(actual, expected);
     }
 
+    #[test]
+    fn test_scale_fn_with_strategy() {
+        scale_fn!(bytes_early,
+                  base: B1000,
+                  constraint: UnitAndAbove,
+                  strategy: crate::strategy::Threshold(0.9),
+                  mantissa_fmt: "{:.2}",
+                  unit: "B",
+                  doc: "Print a value in bytes, switching prefix at 90%.");
+
+        let actual = format!("result is {}", bytes_early(950_000));
+        let expected = "result is 0.95 MB";
+        assert_eq!(actual, expected);
+
+        let actual = format!("result is {}", bytes_early(850_000));
+        let expected = "result is 850.00 kB";
+        assert_eq!(actual, expected);
+
+        scale_fn!(meters_early,
+                  unit: crate::quantity::units::METER,
+                  strategy: crate::strategy::Threshold(0.9),
+                  mantissa_fmt: "{:.2}",
+                  doc: "Print a value in meters, switching prefix at 90%.");
+
+        let actual = format!("result is {}", meters_early(950));
+        let expected = "result is 0.95 km";
+        assert_eq!(actual, expected);
+
+        let actual = format!("result is {}", meters_early(850));
+        let expected = "result is 850.00 m";
+        assert_eq!(actual, expected);
+    }
+
     /// Test that usize, u64, i64, isize work with helper functions.
     /// See https://github.com/graelo/si-scale/issues/4
     #[cfg(feature = "lossy-conversions")]
@@ -0,0 +1,192 @@
+//! Stateful scaling for live-updating displays, such as progress bars or
+//! gauges.
+//!
+//! A value oscillating around a prefix boundary would flicker between, say,
+//! `999 kB` and `1.00 MB`. A [`Hysteresis`] remembers the last selected
+//! prefix and only switches to another one when the value moves past the
+//! boundary by a relative margin.
+//!
+//! # Example
+//!
+//! ```
+//! use si_scale::format_value;
+//! use si_scale::hysteresis::Hysteresis;
+//! use si_scale::prelude::{Base, Constraint};
+//!
+//! let mut gauge = Hysteresis::new(Base::B1000, Constraint::UnitAndAbove, 0.05);
+//!
+//! let v = gauge.value(999_000);
+//! assert_eq!(format!("{}B", format_value!(v, "{:.0}")), "999 kB");
+//!
+//! // Not far enough past 1 MB: the prefix does not change.
+//! let v = gauge.value(1_001_000);
+//! assert_eq!(format!("{}B", format_value!(v, "{:.0}")), "1001 kB");
+//!
+//! let v = gauge.value(1_060_000);
+//! assert_eq!(format!("{}B", format_value!(v, "{:.2}")), "1.06 MB");
+//!
+//! // Not far enough below 1 MB either.
+//! let v = gauge.value(999_000);
+//! assert_eq!(format!("{}B", format_value!(v, "{:.3}")), "0.999 MB");
+//! ```
+
+use crate::base::Base;
+use crate::math;
+use crate::prefix::{Constraint, Prefix};
+use crate::strategy::{next_prefix, Floor, Strategy};
+use crate::value::{IntoF64, Value};
+
+/// Scales successive values, keeping the last selected prefix until values
+/// move past the prefix boundaries by a relative `margin`.
+///
+/// With a margin of `0.05`, values must exceed `1.05 M` to switch from `k`
+/// to `M`, and fall below `0.95 M` to switch back from `M` to `k`.
+/// Values moving further than the adjacent allowed prefix switch
+/// immediately.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Hysteresis {
+    base: Base,
+    constraint: Constraint,
+    margin: f64,
+    last: Option<Prefix>,
+}
+
+impl Hysteresis {
+    /// Returns a `Hysteresis` scaling values in `base`, respecting the
+    /// prefix `constraint`, and switching prefixes only when values are
+    /// past the boundaries by the relative `margin`.
+    pub fn new(base: Base, constraint: Constraint, margin: f64) -> Self {
+        Hysteresis {
+            base,
+            constraint,
+            margin,
+            last: None,
+        }
+    }
+
+    /// Returns the `Value` representing `x`, keeping the last selected
+    /// prefix if `x` is within the margin of its boundaries, and remembers
+    /// the selected prefix.
+    pub fn value<F>(&mut self, x: F) -> Value
+    where
+        F: IntoF64,
+    {
+        let x: f64 = x.into_f64();
+        let candidate = Floor.select(x, &self.base, &self.constraint);
+        // The margin only applies when moving to an adjacent prefix: larger
+        // moves switch immediately.
+        let prefix = match self.last {
+            Some(last)
+                if candidate > last && candidate == next_prefix(last, 1, &self.constraint) =>
+            {
+                // Stay below the boundary of the candidate prefix until the
+                // value exceeds it by the margin.
+                let boundary = self.base.pow(candidate.exponent());
+                if math::abs(x) < boundary * (1.0 + self.margin) {
+                    last
+                } else {
+                    candidate
+                }
+            }
+            Some(last)
+                if candidate < last && candidate == next_prefix(last, -1, &self.constraint) =>
+            {
+                let boundary = self.base.pow(last.exponent());
+                if math::abs(x) >= boundary * (1.0 - self.margin) {
+                    last
+                } else {
+                    candidate
+                }
+            }
+            _ => candidate,
+        };
+        self.last = Some(prefix);
+
+        Value {
+            mantissa: x / self.base.pow(prefix.exponent()),
+            prefix,
+            base: self.base,
+        }
+    }
+
+    /// Returns the last selected prefix, if any.
+    pub fn last_prefix(&self) -> Option<Prefix> {
+        self.last
+    }
+
+    /// Forgets the last selected prefix, so that the next value is scaled
+    /// as with [`Value::new_with()`].
+    pub fn reset(&mut self) {
+        self.last = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn first_value_is_not_delayed() {
+        let mut gauge = Hysteresis::new(Base::B1000, Constraint::None, 0.05);
+        assert_eq!(gauge.last_prefix(), None);
+
+        let actual = gauge.value(1_001_000);
+        let expected = Value::new(1_001_000);
+        assert_eq!(actual, expected);
+        assert_eq!(gauge.last_prefix(), Some(Prefix::Mega));
+    }
+
+    #[test]
+    fn switches_past_the_margin() {
+        let mut gauge = Hysteresis::new(Base::B1000, Constraint::None, 0.05);
+
+        assert_eq!(gauge.value(999.0).prefix, Prefix::Unit);
+        assert_eq!(gauge.value(1001.0).prefix, Prefix::Unit);
+        assert_eq!(gauge.value(1049.0).prefix, Prefix::Unit);
+        assert_eq!(gauge.value(1050.0).prefix, Prefix::Kilo);
+        assert_eq!(gauge.value(999.0).prefix, Prefix::Kilo);
+        assert_eq!(gauge.value(950.0).prefix, Prefix::Kilo);
+        assert_eq!(gauge.value(949.0).prefix, Prefix::Unit);
+
+        let value = gauge.value(-1001.0);
+        assert_eq!(value.prefix, Prefix::Unit);
+        assert_eq!(value.mantissa, -1001.0);
+    }
+
+    #[test]
+    fn large_moves_switch_immediately() {
+        let mut gauge = Hysteresis::new(Base::B1000, Constraint::None, 0.05);
+
+        assert_eq!(gauge.value(999.0).prefix, Prefix::Unit);
+        assert_eq!(gauge.value(2.5e9).prefix, Prefix::Giga);
+        assert_eq!(gauge.value(1.01e6).prefix, Prefix::Mega);
+        assert_eq!(gauge.value(1.2e-3).prefix, Prefix::Milli);
+
+        // Giga is not adjacent to Milli: the margin does not apply.
+        assert_eq!(gauge.value(1.01e9).prefix, Prefix::Giga);
+        assert_eq!(gauge.value(0.99e6).prefix, Prefix::Kilo);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn respects_the_constraint() {
+        let constraint = Constraint::Custom(vec![Prefix::Unit, Prefix::Mega]);
+        let mut gauge = Hysteresis::new(Base::B1000, constraint, 0.05);
+
+        assert_eq!(gauge.value(999_000.0).prefix, Prefix::Unit);
+        assert_eq!(gauge.value(1_001_000.0).prefix, Prefix::Unit);
+        assert_eq!(gauge.value(1_100_000.0).prefix, Prefix::Mega);
+        assert_eq!(gauge.value(990_000.0).prefix, Prefix::Mega);
+    }
+
+    #[test]
+    fn reset_forgets_the_last_prefix() {
+        let mut gauge = Hysteresis::new(Base::B1024, Constraint::UnitAndAbove, 0.1);
+
+        assert_eq!(gauge.value(1000.0).prefix, Prefix::Unit);
+        assert_eq!(gauge.value(1030.0).prefix, Prefix::Unit);
+        gauge.reset();
+        assert_eq!(gauge.last_prefix(), None);
+        assert_eq!(gauge.value(1030.0).prefix, Prefix::Kilo);
+    }
+}
@@ -1,26 +1,88 @@
 #![warn(missing_docs)]
 #![allow(clippy::needless_doctest_main)]
-#![doc = include_str!("../README.md")]
+// The README examples use the `String`-returning helpers.
+#![cfg_attr(feature = "alloc", doc = include_str!("../README.md"))]
+#![cfg_attr(
+    not(feature = "alloc"),
+    doc = "Format values using the appropriate SI scale: `seconds(3.1e-7)` -> `310 ns`.\n\n\
+           See the README for examples, which require the `alloc` feature."
+)]
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+#[cfg(not(any(feature = "std", feature = "libm")))]
+compile_error!("si-scale requires either the `std` or the `libm` feature");
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
+#[cfg(feature = "alloc")]
+use alloc::string::String;
 
 /// Error type used by this crate.
+#[cfg(feature = "alloc")]
 #[derive(Debug, PartialEq, Eq)]
 pub enum SIUnitsError {
     /// Indicates an error occurred when parsing the exponent.
     ExponentParsing(String),
+    /// Indicates that a prefix constraint is invalid, for instance because it
+    /// does not allow any prefix.
+    InvalidConstraint(String),
+    /// Indicates an error occurred when parsing a quantity with its unit.
+    UnitParsing(String),
+    /// Indicates that quantities of different dimensions were added or
+    /// subtracted.
+    IncompatibleDimensions(String),
 }
 
 /// Result type used by this crate.
-pub type Result<T> = std::result::Result<T, SIUnitsError>;
+#[cfg(feature = "alloc")]
+pub type Result<T> = core::result::Result<T, SIUnitsError>;
 
 pub mod base;
+pub mod buffer;
+pub mod compact;
+#[cfg(feature = "decimal")]
+pub mod decimal;
+#[cfg(feature = "defmt")]
+pub mod defmt;
+#[cfg(feature = "alloc")]
+pub mod dimension;
+#[cfg(feature = "alloc")]
+pub mod duration;
+pub mod exact;
+pub mod fixed;
 pub mod format;
+#[cfg(feature = "alloc")]
 pub mod helpers;
+pub mod hysteresis;
+#[cfg(feature = "alloc")]
+pub mod mass;
+mod math;
 pub mod prefix;
+#[cfg(feature = "alloc")]
+pub mod quantity;
+#[cfg(feature = "alloc")]
+pub mod rate;
+pub mod strategy;
+#[cfg(feature = "alloc")]
+pub mod typed;
 pub mod value;
+#[cfg(feature = "alloc")]
+pub mod words;
+
+/// Items used by the expansion of this crate's macros.
+#[cfg(feature = "alloc")]
+#[doc(hidden)]
+pub mod __private {
+    pub use alloc::format;
+    pub use alloc::string::String;
+}
 
 /// Holds first-class citizens of this crate, for convenience.
 pub mod prelude {
-    pub use crate::base::Base;
-    pub use crate::prefix::{Constraint, Prefix};
+    pub use crate::base::{Base, MyriadSymbols};
+    pub use crate::prefix::{Constraint, Prefix, PrefixSet};
+    #[cfg(feature = "alloc")]
+    pub use crate::quantity::{Quantity, Unit};
     pub use crate::value::{IntoF64, Value};
 }
@@ -0,0 +1,166 @@
+//! Masses, provided in kilograms.
+//!
+//! The kilogram is the SI unit of mass, but its symbol already carries a
+//! prefix: a mass of `0.0015 kg` is displayed as `1.5 g`, never as
+//! `1.5 mkg`. Large masses are displayed in tonnes (`2.3 t`, `4.5 kt`) or
+//! in megagrams (`2.3 Mg`), depending on the [`MassStyle`].
+//!
+//! # Example
+//!
+//! ```
+//! use si_scale::mass::{parse_kilograms, Mass, MassStyle};
+//!
+//! assert_eq!(format!("{}", Mass::new(0.0015)), "1.5 g");
+//! assert_eq!(format!("{}", Mass::new(72)), "72 kg");
+//! assert_eq!(format!("{:.1}", Mass::new(2345)), "2.3 t");
+//! assert_eq!(format!("{:.1}", Mass::new(2345).style(MassStyle::Megagrams)), "2.3 Mg");
+//!
+//! assert_eq!(parse_kilograms("1.5 mg").unwrap(), 1.5e-6);
+//! assert_eq!(parse_kilograms("2.3 t").unwrap(), 2300.0);
+//! ```
+
+use core::fmt;
+
+use crate::prefix::Prefix;
+use crate::quantity::{units, Quantity};
+use crate::value::IntoF64;
+use crate::Result;
+
+/// Selects how masses of `1000 kg` and above are displayed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum MassStyle {
+    /// Tonnes with SI prefixes: `2.3 t`, `4.5 kt`, `1.2 Mt`.
+    #[default]
+    Tonnes,
+    /// Grams with SI prefixes: `2.3 Mg`, `4.5 Gg`, `1.2 Tg`.
+    Megagrams,
+}
+
+/// Displays a mass provided in kilograms.
+///
+/// The mantissa is displayed with `"{}"`, unless a precision is provided to
+/// the formatter, as in `"{:.1}"`, and the alternate form `"{:#}"` spells out
+/// the unit names, as in `1.5 grams`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Mass {
+    kilograms: f64,
+    style: MassStyle,
+}
+
+impl Mass {
+    /// Returns a `Mass` of `kilograms`, displaying large masses in tonnes.
+    pub fn new<F>(kilograms: F) -> Self
+    where
+        F: IntoF64,
+    {
+        Mass {
+            kilograms: kilograms.into_f64(),
+            style: MassStyle::Tonnes,
+        }
+    }
+
+    /// Returns self using the provided `style` for large masses.
+    pub fn style(self, style: MassStyle) -> Self {
+        Mass { style, ..self }
+    }
+
+    /// Returns the mass in kilograms.
+    pub fn kilograms(&self) -> f64 {
+        self.kilograms
+    }
+
+    /// Returns the quantity used to display the mass, in grams or tonnes.
+    pub fn to_quantity(&self) -> Quantity {
+        let grams = Quantity::new(self.kilograms, units::KILOGRAM);
+        match self.style {
+            MassStyle::Tonnes if grams.value.prefix >= Prefix::Mega => {
+                Quantity::new(self.kilograms, units::TONNE)
+            }
+            _ => grams,
+        }
+    }
+}
+
+impl fmt::Display for Mass {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.to_quantity(), f)
+    }
+}
+
+/// Prefixed tonnes, listed explicitly because other prefixes are unusual
+/// and more likely typos, such as `mt` for `mg` or `Mt`, and because a bare
+/// `t` after a prefix would also match symbols such as `ft` or `pt`.
+pub(crate) const PREFIXED_TONNES: [(&str, Prefix); 3] = [
+    ("kt", Prefix::Kilo),
+    ("Mt", Prefix::Mega),
+    ("Gt", Prefix::Giga),
+];
+
+/// Parses a mass such as `"1.5 mg"`, `"72 kg"`, `"2.3 Mg"` or `"2.3 t"`,
+/// and returns it in kilograms.
+///
+/// Tonnes are only accepted without prefix or as `kt`, `Mt` and `Gt`.
+///
+/// # Errors
+///
+/// Returns [`SIUnitsError::UnitParsing`][`crate::SIUnitsError::UnitParsing`]
+/// if the string is neither a mass in grams nor in tonnes.
+pub fn parse_kilograms(s: &str) -> Result<f64> {
+    units::KILOGRAM
+        .parse(s)
+        .or_else(|error| match units::TONNE.split(s) {
+            Some((_, prefix))
+                if prefix == Prefix::Unit
+                    || PREFIXED_TONNES.iter().any(|(_, known)| *known == prefix) =>
+            {
+                units::TONNE.parse(s)
+            }
+            _ => Err(error),
+        })
+        .map(|quantity| quantity.to_f64())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn display_masses() {
+        assert_eq!(format!("{}", Mass::new(0)), "0 g");
+        assert_eq!(format!("{}", Mass::new(1.5e-6)), "1.5 mg");
+        assert_eq!(format!("{}", Mass::new(0.0015)), "1.5 g");
+        assert_eq!(format!("{}", Mass::new(0.5)), "500 g");
+        assert_eq!(format!("{}", Mass::new(72)), "72 kg");
+        assert_eq!(format!("{}", Mass::new(-72)), "-72 kg");
+        assert_eq!(format!("{}", Mass::new(999)), "999 kg");
+        assert_eq!(format!("{}", Mass::new(1000)), "1 t");
+        assert_eq!(format!("{}", Mass::new(2.3e3)), "2.3 t");
+        assert_eq!(format!("{}", Mass::new(4.5e6)), "4.5 kt");
+        assert_eq!(format!("{:.2}", Mass::new(1.234e9)), "1.23 Mt");
+        assert_eq!(format!("{:#}", Mass::new(0.0015)), "1.5 grams");
+        assert_eq!(format!("{:#}", Mass::new(2e3)), "2 tonnes");
+    }
+
+    #[test]
+    fn display_megagrams() {
+        let style = MassStyle::Megagrams;
+        assert_eq!(format!("{}", Mass::new(72).style(style)), "72 kg");
+        assert_eq!(format!("{}", Mass::new(2.3e3).style(style)), "2.3 Mg");
+        assert_eq!(format!("{}", Mass::new(4.5e6).style(style)), "4.5 Gg");
+    }
+
+    #[test]
+    fn parse_masses() {
+        assert_eq!(parse_kilograms("72 kg").unwrap(), 72.0);
+        assert_eq!(parse_kilograms("500 g").unwrap(), 0.5);
+        assert_eq!(parse_kilograms("1.5 mg").unwrap(), 1.5e-6);
+        assert_eq!(parse_kilograms("2.3 Mg").unwrap(), 2300.0);
+        assert_eq!(parse_kilograms("2.3 t").unwrap(), 2300.0);
+        assert_eq!(parse_kilograms("4.5 kt").unwrap(), 4.5e6);
+        assert_eq!(parse_kilograms("1.2 Gt").unwrap(), 1.2e12);
+        assert!(parse_kilograms("2.3 mt").is_err());
+        assert!(parse_kilograms("2.3 Tt").is_err());
+        assert!(parse_kilograms("4.5 kB").is_err());
+        assert!(parse_kilograms("kg").is_err());
+    }
+}
@@ -0,0 +1,115 @@
+//! Float maths from the standard library, or from `libm` without it.
+
+#[cfg(feature = "std")]
+mod imp {
+    pub fn abs(x: f64) -> f64 {
+        x.abs()
+    }
+
+    pub fn floor(x: f64) -> f64 {
+        x.floor()
+    }
+
+    pub fn fract(x: f64) -> f64 {
+        x.fract()
+    }
+
+    pub fn ln(x: f64) -> f64 {
+        x.ln()
+    }
+
+    pub fn log2(x: f64) -> f64 {
+        x.log2()
+    }
+
+    pub fn log10(x: f64) -> f64 {
+        x.log10()
+    }
+
+    pub fn powf(x: f64, y: f64) -> f64 {
+        x.powf(y)
+    }
+
+    pub fn powi(x: f64, n: i32) -> f64 {
+        x.powi(n)
+    }
+
+    #[cfg(feature = "alloc")]
+    pub fn round(x: f64) -> f64 {
+        x.round()
+    }
+
+    pub fn signum(x: f64) -> f64 {
+        x.signum()
+    }
+}
+
+#[cfg(all(not(feature = "std"), feature = "libm"))]
+mod imp {
+    pub fn abs(x: f64) -> f64 {
+        libm::fabs(x)
+    }
+
+    pub fn floor(x: f64) -> f64 {
+        libm::floor(x)
+    }
+
+    pub fn fract(x: f64) -> f64 {
+        x - libm::trunc(x)
+    }
+
+    pub fn ln(x: f64) -> f64 {
+        libm::log(x)
+    }
+
+    pub fn log2(x: f64) -> f64 {
+        libm::log2(x)
+    }
+
+    pub fn log10(x: f64) -> f64 {
+        libm::log10(x)
+    }
+
+    /// Powers of ten from `1e-24` to `1e32`, which are correctly rounded
+    /// when read from literals, unlike the results of `libm::pow()`.
+    const POWERS_OF_TEN: [f64; 57] = [
+        1e-24, 1e-23, 1e-22, 1e-21, 1e-20, 1e-19, 1e-18, 1e-17, 1e-16, 1e-15, 1e-14, 1e-13, 1e-12,
+        1e-11, 1e-10, 1e-9, 1e-8, 1e-7, 1e-6, 1e-5, 1e-4, 1e-3, 1e-2, 1e-1, 1e0, 1e1, 1e2, 1e3,
+        1e4, 1e5, 1e6, 1e7, 1e8, 1e9, 1e10, 1e11, 1e12, 1e13, 1e14, 1e15, 1e16, 1e17, 1e18, 1e19,
+        1e20, 1e21, 1e22, 1e23, 1e24, 1e25, 1e26, 1e27, 1e28, 1e29, 1e30, 1e31, 1e32,
+    ];
+
+    pub fn powf(x: f64, y: f64) -> f64 {
+        let exponent = if x == 10.0 {
+            y
+        } else if x == 1000.0 {
+            3.0 * y
+        } else {
+            return libm::pow(x, y);
+        };
+        if exponent == libm::trunc(exponent) && (-24.0..=32.0).contains(&exponent) {
+            POWERS_OF_TEN[(exponent + 24.0) as usize]
+        } else {
+            libm::pow(x, y)
+        }
+    }
+
+    pub fn powi(x: f64, n: i32) -> f64 {
+        powf(x, n.into())
+    }
+
+    #[cfg(feature = "alloc")]
+    pub fn round(x: f64) -> f64 {
+        libm::round(x)
+    }
+
+    pub fn signum(x: f64) -> f64 {
+        if x.is_nan() {
+            f64::NAN
+        } else {
+            libm::copysign(1.0, x)
+        }
+    }
+}
+
+pub(crate) use imp::*;
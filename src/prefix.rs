@@ -1,9 +1,13 @@
 //! The prefix is added to the unit string representation, such as `µ` in `µs`,
 //! and requires the mantissa to be scaled accordingly.
 
-use std::convert::TryFrom;
-use std::fmt;
-use std::str::FromStr;
+#[cfg(feature = "alloc")]
+use alloc::{format, string::ToString};
+#[cfg(feature = "alloc")]
+use core::convert::TryFrom;
+use core::fmt;
+#[cfg(feature = "alloc")]
+use core::str::FromStr;
 
 /// Concise definition of constraints on the prefix.
 ///
@@ -11,12 +15,25 @@ use std::str::FromStr;
 /// towards higher scales such as `k` or `M`, or towards lower scales such as
 /// `µ`.
 ///
-/// You can also define your custom constraint.
+/// You can also define your custom constraint, for instance a range of
+/// prefixes, a single prefix, or all prefixes but some, using a
+/// [`PrefixSet`].
 pub mod constraint {
+    #[cfg(feature = "alloc")]
+    use alloc::{string::ToString, vec::Vec};
+    use core::ops::RangeInclusive;
+
     use super::Prefix;
+    #[cfg(feature = "alloc")]
+    use crate::{Result, SIUnitsError};
 
     /// Specifies a constraint on the SI [`Prefix`].
+    ///
+    /// The enum is non-exhaustive because the `Custom` variant only exists
+    /// with the `alloc` feature: matches need a wildcard arm to compile with
+    /// any set of features.
     #[derive(Debug, Clone, PartialEq, Eq)]
+    #[non_exhaustive]
     pub enum Constraint {
         /// Allows all prefixes from `Yocto` to `Yotta`.
         ///
@@ -29,9 +46,163 @@ pub mod constraint {
         UnitAndAbove,
         /// Only allows prefixes from `Yocto` to `Unit`.
         UnitAndBelow,
-        /// Only allows the specified prefixes (should be sorted in ascending
-        /// exponent order).
+        /// Only allows the specified prefixes, in any order. Requires the
+        /// `alloc` feature.
+        #[cfg(feature = "alloc")]
         Custom(Vec<Prefix>),
+        /// Only allows the prefixes of the set, which needs not be sorted.
+        ///
+        /// Unlike `Custom`, this can be used in `const` items:
+        ///
+        /// ```
+        /// use si_scale::prelude::{Constraint, Prefix, PrefixSet};
+        ///
+        /// const UP_TO_GIGA: Constraint =
+        ///     Constraint::Set(PrefixSet::range(Prefix::Unit, Prefix::Giga));
+        /// ```
+        Set(PrefixSet),
+    }
+
+    impl Constraint {
+        /// Returns a constraint which only allows the provided `prefix`, for
+        /// instance to always display kB.
+        ///
+        /// # Example
+        ///
+        /// ```
+        /// use si_scale::prelude::{Base, Constraint, Prefix, Value};
+        ///
+        /// let value = Value::new_with(12, Base::B1000, Constraint::exact(Prefix::Kilo));
+        /// assert_eq!(value.mantissa, 0.012);
+        /// assert_eq!(value.prefix, Prefix::Kilo);
+        /// ```
+        pub const fn exact(prefix: Prefix) -> Self {
+            Constraint::Set(PrefixSet::single(prefix))
+        }
+
+        /// Returns a constraint which only allows the prefixes of `set`, or
+        /// `None` if the set is empty.
+        pub const fn from_set(set: PrefixSet) -> Option<Self> {
+            if set.is_empty() {
+                None
+            } else {
+                Some(Constraint::Set(set))
+            }
+        }
+
+        /// Returns a constraint which allows all prefixes of the provided
+        /// `range`, or `None` if the range is empty. Unlike
+        /// [`Constraint::range()`], this does not require the `alloc`
+        /// feature and can be used in `const` items.
+        ///
+        /// # Example
+        ///
+        /// ```
+        /// use si_scale::prelude::{Constraint, Prefix};
+        ///
+        /// const UP_TO_GIGA: Option<Constraint> = Constraint::checked_range(Prefix::Unit..=Prefix::Giga);
+        /// assert!(UP_TO_GIGA.is_some());
+        ///
+        /// assert_eq!(Constraint::checked_range(Prefix::Giga..=Prefix::Milli), None);
+        /// ```
+        pub const fn checked_range(range: RangeInclusive<Prefix>) -> Option<Self> {
+            Self::from_set(PrefixSet::range(*range.start(), *range.end()))
+        }
+
+        /// Returns a constraint which allows all prefixes except the
+        /// `excluded` ones, or `None` if no prefix is left. Unlike
+        /// [`Constraint::excluding()`], this does not require the `alloc`
+        /// feature and can be used in `const` items.
+        pub const fn checked_excluding(excluded: &[Prefix]) -> Option<Self> {
+            Self::from_set(PrefixSet::excluding(excluded))
+        }
+
+        /// Returns a constraint which only allows the provided prefixes, in
+        /// any order, or `None` if `allowed` is empty. Unlike
+        /// [`Constraint::custom()`], this does not require the `alloc`
+        /// feature and can be used in `const` items.
+        pub const fn checked_custom(allowed: &[Prefix]) -> Option<Self> {
+            Self::from_set(PrefixSet::from_slice(allowed))
+        }
+
+        /// Returns a constraint which allows all prefixes of the provided
+        /// `range`, or an error if the range is empty.
+        ///
+        /// # Example
+        ///
+        /// ```
+        /// use si_scale::prelude::{Base, Constraint, Prefix, Value};
+        ///
+        /// let constraint = Constraint::range(Prefix::Milli..=Prefix::Giga).unwrap();
+        /// let value = Value::new_with(1.5e-6, Base::B1000, &constraint);
+        /// assert_eq!(value.prefix, Prefix::Milli);
+        ///
+        /// assert!(Constraint::range(Prefix::Giga..=Prefix::Milli).is_err());
+        /// ```
+        #[cfg(feature = "alloc")]
+        pub fn range(range: RangeInclusive<Prefix>) -> Result<Self> {
+            let set = PrefixSet::range(*range.start(), *range.end());
+            Constraint::Set(set).checked()
+        }
+
+        /// Returns a constraint which allows all prefixes except the
+        /// `excluded` ones, or an error if no prefix is left.
+        ///
+        /// # Example
+        ///
+        /// ```
+        /// use si_scale::prelude::{Base, Constraint, Prefix, Value};
+        ///
+        /// // Never display µ.
+        /// let constraint = Constraint::excluding(&[Prefix::Micro]).unwrap();
+        /// let value = Value::new_with(1.5e-5, Base::B1000, &constraint);
+        /// assert_eq!(value.prefix, Prefix::Nano);
+        /// ```
+        #[cfg(feature = "alloc")]
+        pub fn excluding(excluded: &[Prefix]) -> Result<Self> {
+            Constraint::Set(PrefixSet::excluding(excluded)).checked()
+        }
+
+        /// Returns a constraint which only allows the provided prefixes, in
+        /// any order, or an error if `allowed` is empty.
+        #[cfg(feature = "alloc")]
+        pub fn custom(allowed: &[Prefix]) -> Result<Self> {
+            Constraint::Set(PrefixSet::from_slice(allowed)).checked()
+        }
+
+        /// Returns an error if the constraint does not allow any prefix.
+        ///
+        /// The prefixes of a `Custom` constraint may be in any order.
+        #[cfg(feature = "alloc")]
+        pub fn validate(&self) -> Result<()> {
+            match self {
+                Constraint::Custom(allowed) if allowed.is_empty() => Err(
+                    SIUnitsError::InvalidConstraint("no prefix is allowed".to_string()),
+                ),
+                Constraint::Set(set) if set.is_empty() => Err(SIUnitsError::InvalidConstraint(
+                    "no prefix is allowed".to_string(),
+                )),
+                _ => Ok(()),
+            }
+        }
+
+        /// Returns the set of prefixes allowed by the constraint.
+        pub(crate) fn to_set(&self) -> PrefixSet {
+            match self {
+                Constraint::None => PrefixSet::ALL,
+                Constraint::UnitOnly => PrefixSet::single(Prefix::Unit),
+                Constraint::UnitAndAbove => PrefixSet::range(Prefix::Unit, Prefix::Yotta),
+                Constraint::UnitAndBelow => PrefixSet::range(Prefix::Yocto, Prefix::Unit),
+                #[cfg(feature = "alloc")]
+                Constraint::Custom(allowed) => PrefixSet::from_slice(allowed),
+                Constraint::Set(set) => *set,
+            }
+        }
+
+        #[cfg(feature = "alloc")]
+        fn checked(self) -> Result<Self> {
+            self.validate().map(|_| self)
+        }
     }
 
     impl AsRef<Constraint> for Constraint {
@@ -39,15 +210,128 @@ pub mod constraint {
             self
         }
     }
+
+    impl From<PrefixSet> for Constraint {
+        fn from(set: PrefixSet) -> Self {
+            Constraint::Set(set)
+        }
+    }
+
+    /// A set of prefixes, stored as a bitset, so it is `Copy` and can be
+    /// built in `const` contexts.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use si_scale::prelude::{Prefix, PrefixSet};
+    ///
+    /// const SET: PrefixSet = PrefixSet::range(Prefix::Milli, Prefix::Giga)
+    ///     .without(Prefix::Unit);
+    ///
+    /// assert!(SET.contains(Prefix::Kilo));
+    /// assert!(!SET.contains(Prefix::Unit));
+    /// assert_eq!(SET.iter().next(), Some(Prefix::Milli));
+    /// ```
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    pub struct PrefixSet(u32);
+
+    impl PrefixSet {
+        /// The empty set.
+        pub const EMPTY: Self = PrefixSet(0);
+
+        /// The set of all prefixes, from `Yocto` to `Yotta`.
+        pub const ALL: Self = PrefixSet::range(Prefix::Yocto, Prefix::Yotta);
+
+        /// Returns the index of the bit representing `prefix`.
+        const fn bit(prefix: Prefix) -> u32 {
+            ((prefix as i32 - Prefix::Yocto as i32) / 3) as u32
+        }
+
+        /// Returns the set containing only `prefix`.
+        pub const fn single(prefix: Prefix) -> Self {
+            PrefixSet(1 << Self::bit(prefix))
+        }
+
+        /// Returns the set of prefixes from `start` to `end` (incl.), which
+        /// is empty if `start` is greater than `end`.
+        pub const fn range(start: Prefix, end: Prefix) -> Self {
+            let (start, end) = (Self::bit(start), Self::bit(end));
+            if start > end {
+                return Self::EMPTY;
+            }
+            PrefixSet((u32::MAX >> (31 - end)) & (u32::MAX << start))
+        }
+
+        /// Returns the set of the provided prefixes, in any order.
+        pub const fn from_slice(prefixes: &[Prefix]) -> Self {
+            let mut set = Self::EMPTY;
+            let mut i = 0;
+            while i < prefixes.len() {
+                set = set.with(prefixes[i]);
+                i += 1;
+            }
+            set
+        }
+
+        /// Returns the set of all prefixes except the `excluded` ones.
+        pub const fn excluding(excluded: &[Prefix]) -> Self {
+            Self::ALL.difference(Self::from_slice(excluded))
+        }
+
+        /// Returns self with `prefix` added.
+        pub const fn with(self, prefix: Prefix) -> Self {
+            PrefixSet(self.0 | Self::single(prefix).0)
+        }
+
+        /// Returns self with `prefix` removed.
+        pub const fn without(self, prefix: Prefix) -> Self {
+            PrefixSet(self.0 & !Self::single(prefix).0)
+        }
+
+        /// Returns the union of self and `other`.
+        pub const fn union(self, other: Self) -> Self {
+            PrefixSet(self.0 | other.0)
+        }
+
+        /// Returns the prefixes of self which are not in `other`.
+        pub const fn difference(self, other: Self) -> Self {
+            PrefixSet(self.0 & !other.0)
+        }
+
+        /// Returns `true` if `prefix` is in the set.
+        pub const fn contains(&self, prefix: Prefix) -> bool {
+            self.0 & Self::single(prefix).0 != 0
+        }
+
+        /// Returns `true` if the set contains no prefix.
+        pub const fn is_empty(&self) -> bool {
+            self.0 == 0
+        }
+
+        /// Returns the number of prefixes in the set.
+        pub const fn len(&self) -> usize {
+            self.0.count_ones() as usize
+        }
+
+        /// Iterates over the prefixes of the set, in ascending exponent
+        /// order.
+        pub fn iter(&self) -> impl Iterator<Item = Prefix> {
+            let bits = self.0;
+            (0..17)
+                .filter(move |bit| bits & (1 << bit) != 0)
+                .filter_map(|bit| Prefix::from_exponent(Prefix::Yocto as i32 + 3 * bit))
+        }
+    }
 }
 
-pub use constraint::Constraint;
+pub use constraint::{Constraint, PrefixSet};
 
+#[cfg(feature = "alloc")]
 use crate::{Result, SIUnitsError};
 
 /// Represents units' [SI prefixes](https://www.bipm.org/en/measurement-units/si-prefixes).
 ///
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 #[repr(i32)]
 pub enum Prefix {
     /// "yocto" prefix, 1e-24
@@ -100,8 +384,79 @@ impl Prefix {
     pub fn exponent(&self) -> i32 {
         *self as i32
     }
+
+    /// Returns the prefix of the provided `exponent`, or `None` if it is
+    /// not a multiple of 3 between -24 and 24. Unlike `TryFrom<i32>`, this
+    /// does not require the `alloc` feature.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use si_scale::prelude::Prefix;
+    ///
+    /// assert_eq!(Prefix::from_exponent(-6), Some(Prefix::Micro));
+    /// assert_eq!(Prefix::from_exponent(4), None);
+    /// ```
+    pub const fn from_exponent(exponent: i32) -> Option<Self> {
+        match exponent {
+            -24 => Some(Self::Yocto),
+            -21 => Some(Self::Zepto),
+            -18 => Some(Self::Atto),
+            -15 => Some(Self::Femto),
+            -12 => Some(Self::Pico),
+            -9 => Some(Self::Nano),
+            -6 => Some(Self::Micro),
+            -3 => Some(Self::Milli),
+            0 => Some(Self::Unit),
+            3 => Some(Self::Kilo),
+            6 => Some(Self::Mega),
+            9 => Some(Self::Giga),
+            12 => Some(Self::Tera),
+            15 => Some(Self::Peta),
+            18 => Some(Self::Exa),
+            21 => Some(Self::Zetta),
+            24 => Some(Self::Yotta),
+            _ => None,
+        }
+    }
+
+    /// Returns the long name of the prefix, such as `"micro"` or `"kilo"`.
+    ///
+    /// The `Unit` prefix has an empty name, so that the long name can always
+    /// be prepended to a unit name.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use si_scale::prelude::Prefix;
+    ///
+    /// assert_eq!(Prefix::Micro.long_name(), "micro");
+    /// assert_eq!(Prefix::Unit.long_name(), "");
+    /// ```
+    pub fn long_name(&self) -> &'static str {
+        match self {
+            Prefix::Yocto => "yocto",
+            Prefix::Zepto => "zepto",
+            Prefix::Atto => "atto",
+            Prefix::Femto => "femto",
+            Prefix::Pico => "pico",
+            Prefix::Nano => "nano",
+            Prefix::Micro => "micro",
+            Prefix::Milli => "milli",
+            Prefix::Unit => "",
+            Prefix::Kilo => "kilo",
+            Prefix::Mega => "mega",
+            Prefix::Giga => "giga",
+            Prefix::Tera => "tera",
+            Prefix::Peta => "peta",
+            Prefix::Exa => "exa",
+            Prefix::Zetta => "zetta",
+            Prefix::Yotta => "yotta",
+        }
+    }
 }
 
+#[cfg(feature = "alloc")]
 impl FromStr for Prefix {
     type Err = SIUnitsError;
 
@@ -186,35 +541,19 @@ impl fmt::Display for Prefix {
     }
 }
 
+#[cfg(feature = "alloc")]
 impl TryFrom<i32> for Prefix {
     type Error = SIUnitsError;
 
     /// Builds a `Prefix` from a `i32` if successful, otherwise returns a
     /// `SIUnitsError::ExponentParsing()` error.
     fn try_from(value: i32) -> Result<Self> {
-        match value {
-            -24 => Ok(Self::Yocto),
-            -21 => Ok(Self::Zepto),
-            -18 => Ok(Self::Atto),
-            -15 => Ok(Self::Femto),
-            -12 => Ok(Self::Pico),
-            -9 => Ok(Self::Nano),
-            -6 => Ok(Self::Micro),
-            -3 => Ok(Self::Milli),
-            0 => Ok(Self::Unit),
-            3 => Ok(Self::Kilo),
-            6 => Ok(Self::Mega),
-            9 => Ok(Self::Giga),
-            12 => Ok(Self::Tera),
-            15 => Ok(Self::Peta),
-            18 => Ok(Self::Exa),
-            21 => Ok(Self::Zetta),
-            24 => Ok(Self::Yotta),
-            _ => Err(SIUnitsError::ExponentParsing(format!(
+        Prefix::from_exponent(value).ok_or_else(|| {
+            SIUnitsError::ExponentParsing(format!(
                 "Provided value should be a multiple of 3, between -24 and 24, got `{}` instead",
                 value
-            ))),
-        }
+            ))
+        })
     }
 }
 
@@ -244,3 +583,100 @@ impl TryFrom<i32> for Prefix {
 // impl_try_from_num_for_siprefix!(isize);
 // impl_try_from_num_for_siprefix!(f32);
 // impl_try_from_num_for_siprefix!(f64);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn prefix_set_ranges() {
+        assert_eq!(PrefixSet::ALL.len(), 17);
+        assert!(PrefixSet::ALL.contains(Prefix::Yocto));
+        assert!(PrefixSet::ALL.contains(Prefix::Yotta));
+        assert!(PrefixSet::EMPTY.is_empty());
+
+        let set = PrefixSet::range(Prefix::Milli, Prefix::Giga);
+        let actual: Vec<Prefix> = set.iter().collect();
+        let expected = vec![
+            Prefix::Milli,
+            Prefix::Unit,
+            Prefix::Kilo,
+            Prefix::Mega,
+            Prefix::Giga,
+        ];
+        assert_eq!(actual, expected);
+
+        let set = PrefixSet::range(Prefix::Unit, Prefix::Unit);
+        assert_eq!(set, PrefixSet::single(Prefix::Unit));
+
+        let set = PrefixSet::range(Prefix::Kilo, Prefix::Unit);
+        assert!(set.is_empty());
+    }
+
+    #[test]
+    fn prefix_set_operations() {
+        let set = PrefixSet::single(Prefix::Kilo).with(Prefix::Micro);
+        assert_eq!(set.len(), 2);
+        assert!(set.contains(Prefix::Micro));
+        assert!(!set.without(Prefix::Micro).contains(Prefix::Micro));
+
+        assert_eq!(PrefixSet::from_slice(&[Prefix::Kilo, Prefix::Micro]), set);
+        assert_eq!(PrefixSet::excluding(&[]), PrefixSet::ALL);
+        assert_eq!(PrefixSet::excluding(&[Prefix::Kilo]).len(), 16);
+
+        let other = PrefixSet::single(Prefix::Unit).union(set);
+        assert_eq!(other.len(), 3);
+        assert_eq!(other.difference(set), PrefixSet::single(Prefix::Unit));
+    }
+
+    #[test]
+    fn checked_constraints() {
+        const UP_TO_KILO: Option<Constraint> =
+            Constraint::checked_custom(&[Prefix::Kilo, Prefix::Unit]);
+        assert_eq!(
+            UP_TO_KILO,
+            Some(Constraint::Set(PrefixSet::range(
+                Prefix::Unit,
+                Prefix::Kilo
+            )))
+        );
+        assert_eq!(Constraint::checked_custom(&[]), None);
+        assert_eq!(
+            Constraint::checked_range(Prefix::Milli..=Prefix::Milli),
+            Some(Constraint::exact(Prefix::Milli))
+        );
+        assert_eq!(Constraint::checked_range(Prefix::Kilo..=Prefix::Unit), None);
+        assert!(Constraint::checked_excluding(&[Prefix::Micro]).is_some());
+        assert_eq!(
+            Constraint::checked_excluding(&[Prefix::Yocto, Prefix::Yotta]),
+            Some(Constraint::Set(PrefixSet::range(
+                Prefix::Zepto,
+                Prefix::Zetta
+            )))
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn constraint_validation() {
+        assert!(Constraint::None.validate().is_ok());
+        assert!(Constraint::Custom(vec![Prefix::Unit, Prefix::Kilo])
+            .validate()
+            .is_ok());
+        assert!(Constraint::Custom(vec![Prefix::Mega, Prefix::Unit])
+            .validate()
+            .is_ok());
+        assert!(Constraint::Custom(vec![]).validate().is_err());
+
+        assert!(Constraint::custom(&[]).is_err());
+        assert_eq!(
+            Constraint::custom(&[Prefix::Kilo, Prefix::Unit]),
+            Ok(Constraint::Set(PrefixSet::range(
+                Prefix::Unit,
+                Prefix::Kilo
+            )))
+        );
+        assert!(Constraint::excluding(&[]).is_ok());
+        assert!(Constraint::excluding(&PrefixSet::ALL.iter().collect::<Vec<_>>()).is_err());
+    }
+}
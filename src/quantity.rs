@@ -0,0 +1,1135 @@
+//! Values carrying their unit, such as `1.3 µs` or `12.3 MB`.
+//!
+//! A [`Unit`] describes how values of a unit are displayed: its symbol, its
+//! optional long name, its preferred base and its prefix constraint. A
+//! [`Quantity`] pairs a scaled [`Value`] with its `Unit`, so that it can be
+//! displayed directly, and parsed back with [`Unit::parse()`].
+//!
+//! The helper functions such as [`seconds()`][`crate::helpers::seconds()`]
+//! or [`bytes()`][`crate::helpers::bytes()`] are thin wrappers around the
+//! predefined [`units`].
+//!
+//! # Example
+//!
+//! ```
+//! use si_scale::quantity::{units, Quantity};
+//!
+//! let duration = Quantity::new(1.3e-6, units::SECOND);
+//! assert_eq!(format!("{}", duration), "1.3 µs");
+//! assert_eq!(format!("{:.2}", duration), "1.30 µs");
+//! assert_eq!(format!("{:#}", duration), "1.3 microseconds");
+//!
+//! let parsed = units::SECOND.parse("1.3 µs").unwrap();
+//! assert_eq!(parsed.to_f64(), 1.3e-6);
+//!
+//! let size = units::BIBYTE.parse("1.5 MiB").unwrap();
+//! assert_eq!(size.to_f64(), 1.5 * 1024.0 * 1024.0);
+//! ```
+//!
+//! Units with a [`Dimension`] support multiplication and division, which
+//! yield quantities of derived units, re-scaled with appropriate prefixes:
+//!
+//! ```
+//! use si_scale::quantity::{units, Quantity};
+//!
+//! let size = Quantity::new(3e9, units::BYTE);
+//! let duration = Quantity::new(2, units::SECOND);
+//! assert_eq!(format!("{}", size / duration.clone()), "1.5 GB/s");
+//!
+//! let power = Quantity::new(1.5e3, units::WATT);
+//! assert_eq!(format!("{}", power * duration), "3 kJ");
+//!
+//! let period = Quantity::new(2e-9, units::SECOND);
+//! assert_eq!(format!("{:.0}", 1.0 / period), "500 MHz");
+//!
+//! let meters = Quantity::new(12, units::METER);
+//! assert!(meters.checked_add(&Quantity::new(1, units::SECOND)).is_err());
+//! ```
+
+use alloc::borrow::Cow;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::convert::TryFrom;
+use core::fmt;
+use core::ops::{Div, Mul, Neg};
+use core::time::Duration;
+
+use crate::base::Base;
+use crate::dimension::{superscript, Dimension};
+use crate::math;
+use crate::prefix::{Constraint, Prefix, PrefixSet};
+use crate::strategy::Strategy;
+use crate::value::{IntoF64, Value};
+use crate::words::{LongForm, UnitName};
+use crate::{Result, SIUnitsError};
+
+/// Describes a unit: its symbol, its optional long name, and how its values
+/// are scaled.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Unit {
+    symbol: Cow<'static, str>,
+    name: Option<UnitName<'static>>,
+    base: Base,
+    constraint: Constraint,
+    dimension: Option<Dimension>,
+    power: i32,
+    unit_prefix: Prefix,
+}
+
+impl Unit {
+    /// Returns a unit displayed with `symbol`, whose values are scaled in
+    /// `base` with the prefix `constraint`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use si_scale::prelude::{Base, Constraint};
+    /// use si_scale::quantity::{Quantity, Unit};
+    ///
+    /// const BITS_PER_SEC: Unit = Unit::new("bit/s", Base::B1024, Constraint::UnitAndAbove);
+    ///
+    /// let actual = format!("{:.2}", Quantity::new(2.1 * 1024.0, BITS_PER_SEC));
+    /// assert_eq!(actual, "2.10 kibit/s");
+    /// ```
+    pub const fn new(symbol: &'static str, base: Base, constraint: Constraint) -> Self {
+        Unit {
+            symbol: Cow::Borrowed(symbol),
+            name: None,
+            base,
+            constraint,
+            dimension: None,
+            power: 1,
+            unit_prefix: Prefix::Unit,
+        }
+    }
+
+    /// Returns self with the provided long `name`, used by the alternate
+    /// form of [`Quantity`]'s `Display`.
+    pub const fn with_name(mut self, name: UnitName<'static>) -> Self {
+        self.name = Some(name);
+        self
+    }
+
+    /// Returns self with the provided `dimension`, which enables dimensional
+    /// arithmetic between quantities.
+    pub const fn with_dimension(mut self, dimension: Dimension) -> Self {
+        self.dimension = Some(dimension);
+        self
+    }
+
+    /// Returns self raised to `power`, such as square meters for `2`.
+    ///
+    /// Prefixes then scale values by the base raised to `power`, so that
+    /// `1 km²` is `1e6 m²`, and the symbol is displayed with a superscript.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use si_scale::quantity::{units, Quantity};
+    ///
+    /// let area = Quantity::new(2.5e6, units::METER.with_power(2));
+    /// assert_eq!(format!("{}", area), "2.5 km²");
+    ///
+    /// let volume = Quantity::new(2e-9, units::CUBIC_METER);
+    /// assert_eq!(format!("{}", volume), "2 mm³");
+    /// ```
+    pub const fn with_power(mut self, power: i32) -> Self {
+        self.power = power;
+        self
+    }
+
+    /// Returns self where values are provided in the unit with the
+    /// `unit_prefix`, while the symbol is displayed with the prefix of the
+    /// scaled value.
+    ///
+    /// This is how the kilogram, whose symbol already carries a prefix, is
+    /// defined: values are in kilograms, but displayed as `1.5 g`, not as
+    /// `1.5 mkg`. See [`units::KILOGRAM`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use si_scale::prelude::{Base, Constraint, Prefix};
+    /// use si_scale::quantity::{Quantity, Unit};
+    ///
+    /// let kilowatt_hour = Unit::new("Wh", Base::B1000, Constraint::UnitAndAbove)
+    ///     .with_unit_prefix(Prefix::Kilo);
+    /// assert_eq!(format!("{}", Quantity::new(1500, kilowatt_hour)), "1.5 MWh");
+    /// ```
+    pub const fn with_unit_prefix(mut self, unit_prefix: Prefix) -> Self {
+        self.unit_prefix = unit_prefix;
+        self
+    }
+
+    /// Returns self where values are scaled in `base`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use si_scale::prelude::Base;
+    /// use si_scale::quantity::{units, Quantity};
+    ///
+    /// let size = Quantity::new(1536, units::BYTE.with_base(Base::B1024));
+    /// assert_eq!(format!("{}", size), "1.5 kiB");
+    /// ```
+    pub const fn with_base(mut self, base: Base) -> Self {
+        self.base = base;
+        self
+    }
+
+    /// Converts `x`, expressed in the unit with its unit prefix (e.g.
+    /// kilograms), to the unit of the symbol (e.g. grams).
+    fn in_symbol_unit(&self, x: f64) -> f64 {
+        // Dividing by powers of 1000 is exact more often than multiplying by
+        // powers of 0.001.
+        let exponent = self.unit_prefix.exponent();
+        if exponent < 0 {
+            x / Base::B1000.pow_with_power(-exponent, self.power)
+        } else {
+            x * Base::B1000.pow_with_power(exponent, self.power)
+        }
+    }
+
+    /// Inverse of [`Unit::in_symbol_unit()`].
+    fn in_prefixed_unit(&self, x: f64) -> f64 {
+        let exponent = self.unit_prefix.exponent();
+        if exponent < 0 {
+            x * Base::B1000.pow_with_power(-exponent, self.power)
+        } else {
+            x / Base::B1000.pow_with_power(exponent, self.power)
+        }
+    }
+
+    /// Returns the unit of the provided `dimension`.
+    ///
+    /// This is one of the predefined [`units`] if one has this dimension,
+    /// such as `Hz` or `J`, or a power of one of them, such as `m²`, or else
+    /// a unit whose symbol is composed from the
+    /// base dimensions, such as `B/s` or `m⁻¹`. Units with information in
+    /// their numerator are never scaled below the unit, and units without
+    /// numerator are not scaled. The `base` is used for
+    /// composed units and for units of information.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use si_scale::dimension::Dimension;
+    /// use si_scale::prelude::Base;
+    /// use si_scale::quantity::{units, Unit};
+    ///
+    /// assert_eq!(Unit::of_dimension(Dimension::FREQUENCY, Base::B1000), units::HERTZ);
+    ///
+    /// let throughput = Unit::of_dimension(Dimension::BYTES / Dimension::TIME, Base::B1024);
+    /// assert_eq!(throughput.symbol(), "B/s");
+    /// ```
+    pub fn of_dimension(dimension: Dimension, base: Base) -> Self {
+        let predefined = [1i8, 2, 3, -1, -2, -3].iter().find_map(|&power| {
+            units::PREDEFINED
+                .iter()
+                .find(|unit| {
+                    unit.dimension.and_then(|dim| dim.checked_powi(power)) == Some(dimension)
+                })
+                .map(|unit| unit.clone().with_power(i32::from(power)))
+        });
+        match predefined {
+            Some(unit) if dimension.has_information() => Unit { base, ..unit },
+            Some(unit) => unit,
+            None => Unit {
+                symbol: Cow::Owned(dimension.to_string()),
+                name: None,
+                base,
+                // A prefix before `m²/s` or `kg·m/s²` would only apply to
+                // the first unit.
+                constraint: if !dimension.accepts_prefix() {
+                    Constraint::UnitOnly
+                } else if dimension.has_information() {
+                    Constraint::UnitAndAbove
+                } else {
+                    Constraint::None
+                },
+                dimension: Some(dimension),
+                power: 1,
+                unit_prefix: Prefix::Unit,
+            },
+        }
+    }
+
+    /// Returns the symbol of the unit, such as `"s"`, without its power.
+    pub fn symbol(&self) -> &str {
+        &self.symbol
+    }
+
+    /// Returns the symbol of the unit along with its power, such as `"m²"`.
+    pub fn full_symbol(&self) -> Cow<'_, str> {
+        match self.power {
+            1 => Cow::Borrowed(&self.symbol),
+            power => Cow::Owned(format!("{}{}", self.symbol, superscript(power))),
+        }
+    }
+
+    /// Returns the symbol of the unit with its unit prefix and power, such
+    /// as `"kg"` for [`units::KILOGRAM`], in which its values are expressed.
+    pub fn prefixed_symbol(&self) -> Cow<'_, str> {
+        match self.unit_prefix {
+            Prefix::Unit => self.full_symbol(),
+            prefix => Cow::Owned(format!(
+                "{}{}",
+                Base::B1000.prefix_symbol(prefix),
+                self.full_symbol()
+            )),
+        }
+    }
+
+    /// Returns the power of the unit, `1` unless set with
+    /// [`Unit::with_power()`].
+    pub fn power(&self) -> i32 {
+        self.power
+    }
+
+    /// Returns the long name of the unit, if any.
+    pub fn name(&self) -> Option<&UnitName<'static>> {
+        self.name.as_ref()
+    }
+
+    /// Returns the base used to scale values of the unit.
+    pub fn base(&self) -> Base {
+        self.base
+    }
+
+    /// Returns the prefix constraint used to scale values of the unit.
+    pub fn constraint(&self) -> &Constraint {
+        &self.constraint
+    }
+
+    /// Returns the dimension of the unit raised to its power, if known and
+    /// if its exponents fit in an `i8`.
+    pub fn dimension(&self) -> Option<Dimension> {
+        let power = i8::try_from(self.power).ok()?;
+        self.dimension?.checked_powi(power)
+    }
+
+    /// Returns the unit of the product of quantities in `self` and `rhs`.
+    ///
+    /// The unit is composed from both symbols if a dimension is unknown or
+    /// if an exponent of the product overflows.
+    fn product(&self, rhs: &Unit) -> Unit {
+        let dimension = self
+            .dimension()
+            .zip(rhs.dimension())
+            .and_then(|(lhs_dim, rhs_dim)| lhs_dim.checked_mul(rhs_dim));
+        match dimension {
+            Some(dimension) => Unit::of_dimension(dimension, self.common_base(rhs)),
+            None => Unit::composed(
+                format!("{}·{}", self.full_symbol(), rhs.full_symbol()),
+                self.common_base(rhs),
+            ),
+        }
+    }
+
+    /// Returns the unit of the quotient of quantities in `self` and `rhs`.
+    ///
+    /// The unit is composed from both symbols if a dimension is unknown or
+    /// if an exponent of the quotient overflows.
+    fn quotient(&self, rhs: &Unit) -> Unit {
+        let dimension = self
+            .dimension()
+            .zip(rhs.dimension())
+            .and_then(|(lhs_dim, rhs_dim)| lhs_dim.checked_div(rhs_dim));
+        match dimension {
+            Some(dimension) => Unit::of_dimension(dimension, self.common_base(rhs)),
+            None => Unit::composed(
+                format!("{}/{}", self.full_symbol(), rhs.full_symbol()),
+                self.common_base(rhs),
+            ),
+        }
+    }
+
+    /// Returns a unit without dimension, resulting from operations on units
+    /// whose dimension is unknown.
+    fn composed(symbol: String, base: Base) -> Unit {
+        Unit {
+            symbol: Cow::Owned(symbol),
+            name: None,
+            base,
+            constraint: Constraint::None,
+            dimension: None,
+            power: 1,
+            unit_prefix: Prefix::Unit,
+        }
+    }
+
+    /// Returns the base of the result of an operation between `self` and
+    /// `rhs`: the base of the operand carrying information if only one
+    /// does, such as bytes divided by seconds, or else the base of `self`.
+    fn common_base(&self, rhs: &Unit) -> Base {
+        let has_information =
+            |unit: &Unit| unit.dimension().is_some_and(|dim| dim.has_information());
+        if !has_information(self) && has_information(rhs) {
+            rhs.base
+        } else {
+            self.base
+        }
+    }
+
+    /// Returns `true` if quantities of `self` and `rhs` can be added.
+    fn is_compatible_with(&self, rhs: &Unit) -> bool {
+        match (self.dimension(), rhs.dimension()) {
+            (Some(lhs_dim), Some(rhs_dim)) => lhs_dim == rhs_dim,
+            (None, None) => self.full_symbol() == rhs.full_symbol(),
+            _ => false,
+        }
+    }
+
+    /// Parses a string such as `"1.3 µs"` or `"1.5MiB"` into a `Quantity`
+    /// of this unit.
+    ///
+    /// The number may use `_` as thousands separator, and be followed by any
+    /// prefix symbol of the unit's base (`u` is also accepted for `µ`). The
+    /// parsed quantity is re-scaled with the unit's constraint, so `"2 ks"`
+    /// parses as `2000 s`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SIUnitsError::UnitParsing`] if the string does not end with
+    /// the unit symbol, or if the number cannot be parsed.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use si_scale::quantity::units;
+    ///
+    /// let actual = units::SECOND.parse("2 ks").unwrap();
+    /// assert_eq!(format!("{}", actual), "2000 s");
+    ///
+    /// assert!(units::SECOND.parse("2 kB").is_err());
+    /// ```
+    pub fn parse(&self, s: &str) -> Result<Quantity> {
+        let error =
+            || SIUnitsError::UnitParsing(format!("cannot parse {:?} as {}", s, self.full_symbol()));
+
+        let (number, prefix) = self.split(s).ok_or_else(error)?;
+        let mantissa: f64 = number.parse().map_err(|_| error())?;
+        let x = mantissa * self.base.pow_with_power(prefix.exponent(), self.power);
+        Ok(Quantity::new(self.in_prefixed_unit(x), self.clone()))
+    }
+
+    /// Splits `s` into its number, without `_` separators, and the prefix
+    /// in front of the unit symbol, or returns `None` if `s` is not a number
+    /// followed by the (prefixed) symbol of the unit.
+    pub(crate) fn split(&self, s: &str) -> Option<(String, Prefix)> {
+        let rest = s.trim().strip_suffix(&*self.full_symbol())?.trim_end();
+
+        // Symbols of different prefixes may share a suffix (`i` and `ki`),
+        // so longer symbols are tried first.
+        let mut prefixes: Vec<(Prefix, &str)> = PrefixSet::ALL
+            .iter()
+            .map(|prefix| (prefix, self.base.prefix_symbol(prefix)))
+            .filter(|(_, symbol)| !symbol.is_empty())
+            .collect();
+        if self.base == Base::B1000 {
+            prefixes.push((Prefix::Micro, "u"));
+        }
+        prefixes.sort_by_key(|(_, symbol)| core::cmp::Reverse(symbol.len()));
+        prefixes.push((Prefix::Unit, ""));
+
+        prefixes.iter().find_map(|(prefix, symbol)| {
+            let number = rest.strip_suffix(symbol)?.trim_end().replace('_', "");
+            number.parse::<f64>().ok()?;
+            Some((number, *prefix))
+        })
+    }
+}
+
+/// A scaled [`Value`] along with its [`Unit`].
+///
+/// The `Display` implementation writes the mantissa with `"{}"`, unless a
+/// precision is provided to the formatter, as in `"{:.2}"`. The alternate
+/// form `"{:#}"` spells out the prefix and unit names, if the unit has a
+/// name.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Quantity {
+    /// The scaled value.
+    pub value: Value,
+    /// The unit of the value.
+    pub unit: Unit,
+}
+
+impl Quantity {
+    /// Returns a `Quantity` of `x` in `unit`, scaled with the base and
+    /// constraint of the unit. For units with a unit prefix, `x` is
+    /// expressed with that prefix, such as kilograms.
+    pub fn new<F>(x: F, unit: Unit) -> Self
+    where
+        F: IntoF64,
+    {
+        Quantity {
+            value: Value::new_with_power(
+                unit.in_symbol_unit(x.into_f64()),
+                unit.base,
+                &unit.constraint,
+                unit.power,
+            ),
+            unit,
+        }
+    }
+
+    /// Returns a `Quantity` of `x` in `unit` like [`Quantity::new()`], with
+    /// the prefix selected by `strategy` within the constraint of the unit.
+    ///
+    /// For units raised to a power, such as `m²`, the strategy selects the
+    /// prefix of the root of `x`, so that `Floor` keeps the mantissa of
+    /// `2.5e6 m²` between `1` and `1000` squared, as in `2.5 km²`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use si_scale::quantity::{units, Quantity};
+    /// use si_scale::strategy::Threshold;
+    ///
+    /// let size = Quantity::new_with_strategy(950_000, units::BYTE, Threshold(0.9));
+    /// assert_eq!(format!("{}", size), "0.95 MB");
+    /// ```
+    pub fn new_with_strategy<F, S>(x: F, unit: Unit, strategy: S) -> Self
+    where
+        F: IntoF64,
+        S: Strategy,
+    {
+        let x = unit.in_symbol_unit(x.into_f64());
+        let root = match unit.power {
+            0 | 1 => x,
+            power => math::signum(x) * math::powf(math::abs(x), 1f64 / f64::from(power)),
+        };
+        let prefix = strategy.select(root, &unit.base, &unit.constraint);
+        Quantity {
+            value: Value {
+                mantissa: x / unit.base.pow_with_power(prefix.exponent(), unit.power),
+                prefix,
+                base: unit.base,
+            },
+            unit,
+        }
+    }
+
+    /// Converts the quantity to a `f64`, expressed in the unit without
+    /// prefix, or in the unit with its unit prefix, such as kilograms (see
+    /// [`Unit::with_unit_prefix()`]).
+    pub fn to_f64(&self) -> f64 {
+        self.unit
+            .in_prefixed_unit(self.value.to_f64_with_power(self.unit.power))
+    }
+
+    /// Splits the quantity into at most `parts` quantities with integral
+    /// mantissas and descending prefixes, such as `1 GiB 200 MiB 12 kiB`,
+    /// see [`Value::breakdown()`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use si_scale::quantity::{units, Quantity};
+    ///
+    /// let size = Quantity::new((1 << 30) + 200 * (1 << 20) + 12 * (1 << 10) + 5, units::BIBYTE);
+    /// let parts: Vec<String> = size.breakdown(3).iter().map(ToString::to_string).collect();
+    /// assert_eq!(parts.join(" "), "1 GiB 200 MiB 12 kiB");
+    /// ```
+    pub fn breakdown(&self, parts: usize) -> Vec<Quantity> {
+        let power = self.unit.power;
+        let x = self.value.to_f64_with_power(power);
+        Value::breakdown_with_power(x, self.unit.base, &self.unit.constraint, parts, power)
+            .into_iter()
+            .map(|value| Quantity {
+                value,
+                unit: self.unit.clone(),
+            })
+            .collect()
+    }
+
+    /// Returns the sum of `self` and `rhs`, expressed in the unit of `self`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SIUnitsError::IncompatibleDimensions`] if the quantities do
+    /// not have the same dimension.
+    pub fn checked_add(&self, rhs: &Quantity) -> Result<Quantity> {
+        self.check_compatible(rhs, "add")?;
+        Ok(Quantity::new(
+            self.to_f64() + rhs.to_f64(),
+            self.unit.clone(),
+        ))
+    }
+
+    /// Returns the difference of `self` and `rhs`, expressed in the unit of
+    /// `self`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SIUnitsError::IncompatibleDimensions`] if the quantities do
+    /// not have the same dimension.
+    pub fn checked_sub(&self, rhs: &Quantity) -> Result<Quantity> {
+        self.check_compatible(rhs, "subtract")?;
+        Ok(Quantity::new(
+            self.to_f64() - rhs.to_f64(),
+            self.unit.clone(),
+        ))
+    }
+
+    fn check_compatible(&self, rhs: &Quantity, operation: &str) -> Result<()> {
+        if self.unit.is_compatible_with(&rhs.unit) {
+            Ok(())
+        } else {
+            Err(SIUnitsError::IncompatibleDimensions(format!(
+                "cannot {} {} and {}",
+                operation,
+                rhs.unit.full_symbol(),
+                self.unit.full_symbol()
+            )))
+        }
+    }
+
+    /// Returns the spelled-out form of the quantity, such as
+    /// `13 microseconds`, if the unit has a name and is not raised to a
+    /// power.
+    pub fn long_form(&self) -> Option<LongForm<'_>> {
+        match self.unit.power {
+            1 => self
+                .unit
+                .name
+                .as_ref()
+                .map(|name| LongForm::new(&self.value, name)),
+            _ => None,
+        }
+    }
+}
+
+impl From<Duration> for Quantity {
+    /// Returns the quantity of seconds of `duration`, with the mantissa
+    /// computed from its integer number of nanoseconds, see
+    /// [`Value::from_duration()`].
+    fn from(duration: Duration) -> Self {
+        let unit = units::SECOND;
+        Quantity {
+            value: Value::from_duration(duration, unit.constraint()),
+            unit,
+        }
+    }
+}
+
+impl fmt::Display for Quantity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if f.alternate() {
+            if let Some(long_form) = self.long_form() {
+                return match f.precision() {
+                    Some(precision) => write!(f, "{:.*}", precision, long_form),
+                    None => write!(f, "{}", long_form),
+                };
+            }
+        }
+        match f.precision() {
+            Some(precision) => write!(f, "{:.*}", precision, self.value.mantissa)?,
+            None => write!(f, "{}", self.value.mantissa)?,
+        }
+        let prefix = self.value.base.prefix_symbol(self.value.prefix);
+        if !prefix.is_empty() || !self.unit.symbol.is_empty() {
+            f.write_str(self.value.base.separator())?;
+        }
+        write!(f, "{}{}", prefix, self.unit.full_symbol())
+    }
+}
+
+impl Mul for &Quantity {
+    type Output = Quantity;
+
+    fn mul(self, rhs: &Quantity) -> Self::Output {
+        Quantity::new(self.to_f64() * rhs.to_f64(), self.unit.product(&rhs.unit))
+    }
+}
+
+impl Div for &Quantity {
+    type Output = Quantity;
+
+    fn div(self, rhs: &Quantity) -> Self::Output {
+        Quantity::new(self.to_f64() / rhs.to_f64(), self.unit.quotient(&rhs.unit))
+    }
+}
+
+impl Mul<f64> for &Quantity {
+    type Output = Quantity;
+
+    fn mul(self, rhs: f64) -> Self::Output {
+        Quantity::new(self.to_f64() * rhs, self.unit.clone())
+    }
+}
+
+impl Div<f64> for &Quantity {
+    type Output = Quantity;
+
+    fn div(self, rhs: f64) -> Self::Output {
+        Quantity::new(self.to_f64() / rhs, self.unit.clone())
+    }
+}
+
+impl Div<&Quantity> for f64 {
+    type Output = Quantity;
+
+    fn div(self, rhs: &Quantity) -> Self::Output {
+        let unit = match rhs.unit.dimension().and_then(|dim| dim.checked_powi(-1)) {
+            Some(dimension) => Unit::of_dimension(dimension, rhs.unit.base),
+            None => Unit::composed(format!("1/{}", rhs.unit.full_symbol()), rhs.unit.base),
+        };
+        Quantity::new(self / rhs.to_f64(), unit)
+    }
+}
+
+impl Neg for &Quantity {
+    type Output = Quantity;
+
+    fn neg(self) -> Self::Output {
+        Quantity {
+            value: Value {
+                mantissa: -self.value.mantissa,
+                ..self.value
+            },
+            unit: self.unit.clone(),
+        }
+    }
+}
+
+impl Mul for Quantity {
+    type Output = Quantity;
+
+    fn mul(self, rhs: Quantity) -> Self::Output {
+        &self * &rhs
+    }
+}
+
+impl Div for Quantity {
+    type Output = Quantity;
+
+    fn div(self, rhs: Quantity) -> Self::Output {
+        &self / &rhs
+    }
+}
+
+impl Mul<f64> for Quantity {
+    type Output = Quantity;
+
+    fn mul(self, rhs: f64) -> Self::Output {
+        &self * rhs
+    }
+}
+
+impl Div<f64> for Quantity {
+    type Output = Quantity;
+
+    fn div(self, rhs: f64) -> Self::Output {
+        &self / rhs
+    }
+}
+
+impl Div<Quantity> for f64 {
+    type Output = Quantity;
+
+    fn div(self, rhs: Quantity) -> Self::Output {
+        self / &rhs
+    }
+}
+
+impl Neg for Quantity {
+    type Output = Quantity;
+
+    fn neg(self) -> Self::Output {
+        -&self
+    }
+}
+
+/// Predefined units, used by the [helper functions][`crate::helpers`].
+pub mod units {
+    use super::Unit;
+    use crate::base::Base;
+    use crate::dimension::Dimension;
+    use crate::prefix::{Constraint, Prefix};
+    use crate::words::units as names;
+
+    /// Seconds, which are never scaled above the unit: `1.3 µs`, `1200 s`.
+    pub const SECOND: Unit = Unit::new("s", Base::B1000, Constraint::UnitAndBelow)
+        .with_name(names::SECOND)
+        .with_dimension(Dimension::TIME);
+
+    /// Meters, scaled with all prefixes: `1.2 km`, `3 nm`.
+    pub const METER: Unit = Unit::new("m", Base::B1000, Constraint::None)
+        .with_name(names::METER)
+        .with_dimension(Dimension::LENGTH);
+
+    /// Kilograms, the SI unit of mass, displayed in grams with all prefixes:
+    /// `1.5 g` or `2.3 Mg` for `1.5e-3` or `2300` kilograms. See also the
+    /// [`mass`][`crate::mass`] module to display tonnes.
+    pub const KILOGRAM: Unit = Unit::new("g", Base::B1000, Constraint::None)
+        .with_name(names::GRAM)
+        .with_dimension(Dimension::MASS)
+        .with_unit_prefix(Prefix::Kilo);
+
+    /// Tonnes, `1000` kilograms, never scaled below the tonne: `2.3 t`,
+    /// `4.5 kt`. Values are expressed in kilograms.
+    pub const TONNE: Unit = Unit::new("t", Base::B1000, Constraint::UnitAndAbove)
+        .with_name(names::TONNE)
+        .with_dimension(Dimension::MASS)
+        .with_unit_prefix(Prefix::Milli);
+
+    /// Square meters, scaled by steps of `1e6`: `2.5 km²`.
+    pub const SQUARE_METER: Unit = METER.with_power(2);
+
+    /// Cubic meters, scaled by steps of `1e9`: `2 mm³`.
+    pub const CUBIC_METER: Unit = METER.with_power(3);
+
+    /// Bytes in base 1000, never scaled below the unit: `12.3 MB`.
+    pub const BYTE: Unit = Unit::new("B", Base::B1000, Constraint::UnitAndAbove)
+        .with_name(names::BYTE)
+        .with_dimension(Dimension::BYTES);
+
+    /// Bytes in base 1024, never scaled below the unit: `11.8 MiB`.
+    pub const BIBYTE: Unit = Unit::new("B", Base::B1024, Constraint::UnitAndAbove)
+        .with_name(names::BYTE)
+        .with_dimension(Dimension::BYTES);
+
+    /// Bits in base 1000, never scaled below the unit: `1.5 Mbit`.
+    pub const BIT: Unit = Unit::new("bit", Base::B1000, Constraint::UnitAndAbove)
+        .with_name(names::BIT)
+        .with_dimension(Dimension::BITS);
+
+    /// Operations, a count never scaled below the unit: `22 kop`.
+    pub const OPERATION: Unit =
+        Unit::new("op", Base::B1000, Constraint::UnitAndAbove).with_name(names::OPERATION);
+
+    /// Hertz, scaled with all prefixes: `2.4 GHz`.
+    pub const HERTZ: Unit = Unit::new("Hz", Base::B1000, Constraint::None)
+        .with_name(names::HERTZ)
+        .with_dimension(Dimension::FREQUENCY);
+
+    /// Newtons, scaled with all prefixes: `9.8 N`.
+    pub const NEWTON: Unit = Unit::new("N", Base::B1000, Constraint::None)
+        .with_name(names::NEWTON)
+        .with_dimension(Dimension::FORCE);
+
+    /// Joules, scaled with all prefixes: `3 kJ`.
+    pub const JOULE: Unit = Unit::new("J", Base::B1000, Constraint::None)
+        .with_name(names::JOULE)
+        .with_dimension(Dimension::ENERGY);
+
+    /// Watts, scaled with all prefixes: `1.5 kW`.
+    pub const WATT: Unit = Unit::new("W", Base::B1000, Constraint::None)
+        .with_name(names::WATT)
+        .with_dimension(Dimension::POWER);
+
+    /// Units used for the results of dimensional arithmetic, see
+    /// [`Unit::of_dimension()`].
+    pub(crate) const PREDEFINED: [Unit; 9] = [
+        SECOND, METER, KILOGRAM, BYTE, BIT, HERTZ, NEWTON, JOULE, WATT,
+    ];
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::strategy::{Floor, Threshold};
+
+    #[test]
+    fn display_quantities() {
+        let actual = format!("{}", Quantity::new(1.3e-6, units::SECOND));
+        assert_eq!(actual, "1.3 µs");
+
+        let actual = format!("{}", Quantity::new(1234.5, units::SECOND));
+        assert_eq!(actual, "1234.5 s");
+
+        let actual = format!("{:.1}", Quantity::new(12_345_678, units::BYTE));
+        assert_eq!(actual, "12.3 MB");
+
+        let actual = format!("{}", Quantity::new(16, units::BIBYTE));
+        assert_eq!(actual, "16 B");
+
+        let actual = format!("{:.2}", Quantity::new(-2.5e3, units::METER));
+        assert_eq!(actual, "-2.50 km");
+
+        let unitless = Unit::new("", Base::B1000, Constraint::None);
+        assert_eq!(format!("{}", Quantity::new(42, unitless.clone())), "42");
+        assert_eq!(format!("{}", Quantity::new(4.2e3, unitless)), "4.2 k");
+    }
+
+    #[test]
+    fn display_long_form() {
+        let actual = format!("{:#}", Quantity::new(1.3e-5, units::SECOND));
+        assert_eq!(actual, "13 microseconds");
+
+        let actual = format!("{:#.1}", Quantity::new(1024, units::BIBYTE));
+        assert_eq!(actual, "1.0 kibibytes");
+
+        // Without a name, the alternate form uses the symbol.
+        let unit = Unit::new("Hz", Base::B1000, Constraint::None);
+        let actual = format!("{:#}", Quantity::new(2e9, unit));
+        assert_eq!(actual, "2 GHz");
+    }
+
+    #[test]
+    fn parse_quantities() {
+        let actual = units::SECOND.parse("1.3 µs").unwrap();
+        assert_eq!(actual, Quantity::new(1.3e-6, units::SECOND));
+
+        let actual = units::SECOND.parse("  1.3us ").unwrap();
+        assert_eq!(actual, Quantity::new(1.3e-6, units::SECOND));
+
+        let actual = units::SECOND.parse("1_234.5 s").unwrap();
+        assert_eq!(actual.to_f64(), 1234.5);
+
+        let actual = units::BYTE.parse("-12.3 MB").unwrap();
+        assert_eq!(actual.to_f64(), -12.3e6);
+
+        let actual = units::BIBYTE.parse("2 kiB").unwrap();
+        assert_eq!(actual.to_f64(), 2048.0);
+
+        // `m` is both the milli prefix and the meter symbol.
+        let actual = units::METER.parse("5 mm").unwrap();
+        assert_eq!(actual.to_f64(), 5e-3);
+        let actual = units::METER.parse("5 m").unwrap();
+        assert_eq!(actual.to_f64(), 5.0);
+    }
+
+    #[test]
+    fn parse_round_trip() {
+        for x in [1.3e-6, 0.5, 999.0, 1.5e-9] {
+            let quantity = Quantity::new(x, units::SECOND);
+            let parsed = units::SECOND.parse(&quantity.to_string()).unwrap();
+            assert_eq!(parsed, quantity);
+        }
+    }
+
+    #[test]
+    fn multiply_and_divide_quantities() {
+        let size = Quantity::new(3e9, units::BYTE);
+        let duration = Quantity::new(2, units::SECOND);
+        let throughput = &size / &duration;
+        assert_eq!(format!("{}", throughput), "1.5 GB/s");
+        assert_eq!(
+            throughput.unit.dimension(),
+            Some(Dimension::BYTES / Dimension::TIME)
+        );
+        assert_eq!(format!("{}", &throughput * &duration), "3 GB");
+
+        let size = Quantity::new(3 * 1024 * 1024, units::BIBYTE);
+        let duration = Quantity::new(2, units::SECOND.with_base(Base::B1024));
+        assert_eq!(format!("{}", &size / &duration), "1.5 MiB/s");
+        let throughput = &size / &Quantity::new(1, units::SECOND);
+        assert_eq!(format!("{}", throughput), "3 MiB/s");
+        let duration = &Quantity::new(1, units::SECOND) / &size;
+        assert_eq!(duration.unit.base(), Base::B1024);
+
+        let power = Quantity::new(1.5e3, units::WATT);
+        let duration = Quantity::new(2, units::SECOND);
+        let energy = &power * &duration;
+        assert_eq!(energy.unit, units::JOULE);
+        assert_eq!(format!("{}", energy), "3 kJ");
+        assert_eq!(format!("{}", &energy / &power), "2 s");
+
+        let speed = Quantity::new(30, units::METER) / Quantity::new(1e-3, units::SECOND);
+        assert_eq!(format!("{}", speed), "30 km/s");
+
+        let area = Quantity::new(3, units::METER) * Quantity::new(4, units::METER);
+        assert_eq!(format!("{}", area), "12 m²");
+
+        let ratio = &size / &size;
+        assert_eq!(format!("{}", ratio), "1");
+    }
+
+    #[test]
+    fn reciprocal_quantities() {
+        let period = Quantity::new(2e-9, units::SECOND);
+        let frequency = 1.0 / &period;
+        assert_eq!(frequency.unit, units::HERTZ);
+        assert_eq!(format!("{:.0}", frequency), "500 MHz");
+
+        let length = Quantity::new(4, units::METER);
+        assert_eq!(format!("{}", 1.0 / length), "250 km⁻¹");
+
+        let unit = Unit::of_dimension(Dimension::TIME.powi(-1) / Dimension::LENGTH, Base::B1000);
+        assert_eq!(format!("{}", Quantity::new(2e3, unit)), "2000 m⁻¹·s⁻¹");
+    }
+
+    #[test]
+    fn scalar_operations() {
+        let size = Quantity::new(1500, units::BYTE);
+        assert_eq!(format!("{}", &size * 2.0), "3 kB");
+        assert_eq!(format!("{}", &size / 3.0), "500 B");
+        assert_eq!(format!("{}", -size), "-1.5 kB");
+    }
+
+    #[test]
+    fn operations_without_dimensions() {
+        let requests = Unit::new("req", Base::B1000, Constraint::None);
+        let rate = Quantity::new(3e3, requests) / Quantity::new(2, units::SECOND);
+        assert_eq!(format!("{}", rate), "1.5 kreq/s");
+        assert_eq!(rate.unit.dimension(), None);
+    }
+
+    #[test]
+    fn operations_overflowing_dimensions() {
+        let unit = units::SECOND.with_power(127);
+        assert_eq!(unit.dimension(), Dimension::TIME.checked_powi(127));
+        assert_eq!(units::SECOND.with_power(128).dimension(), None);
+
+        let time = Quantity::new(2, unit);
+        let product = &time * &Quantity::new(3, units::SECOND);
+        assert_eq!(product.unit.dimension(), None);
+        assert_eq!(product.to_f64(), 6.0);
+
+        let mut product = Quantity::new(2, units::SECOND);
+        for _ in 0..200 {
+            product = &product * &Quantity::new(1, units::SECOND);
+        }
+        assert_eq!(product.to_f64(), 2.0);
+    }
+
+    #[test]
+    fn add_and_subtract_quantities() {
+        let a = Quantity::new(1.5e3, units::METER);
+        let b = Quantity::new(500, units::METER);
+        assert_eq!(format!("{}", a.checked_add(&b).unwrap()), "2 km");
+        assert_eq!(format!("{}", a.checked_sub(&b).unwrap()), "1 km");
+
+        let size = Quantity::new(1024, units::BIBYTE);
+        let other = Quantity::new(1024, units::BYTE);
+        assert_eq!(format!("{}", size.checked_add(&other).unwrap()), "2 kiB");
+
+        let actual = a.checked_add(&Quantity::new(1, units::SECOND));
+        let expected = Err(SIUnitsError::IncompatibleDimensions(
+            "cannot add s and m".to_string(),
+        ));
+        assert_eq!(actual, expected);
+
+        let bits = Quantity::new(8, units::BIT);
+        assert!(size.checked_sub(&bits).is_err());
+    }
+
+    #[test]
+    fn powered_units() {
+        let area = Quantity::new(2.5e6, units::SQUARE_METER);
+        assert_eq!(format!("{}", area), "2.5 km²");
+        assert_eq!(area.to_f64(), 2.5e6);
+        assert_eq!(area.unit.full_symbol(), "m²");
+        assert_eq!(area.unit.dimension(), Some(Dimension::LENGTH.powi(2)));
+        assert_eq!(format!("{:#}", area), "2.5 km²");
+
+        let area = Quantity::new(5e5, units::SQUARE_METER);
+        assert_eq!(format!("{}", area), "500000 m²");
+
+        let area = Quantity::new_with_strategy(8.1e5, units::SQUARE_METER, Threshold(0.9));
+        assert_eq!(format!("{}", area), "0.81 km²");
+        assert_eq!(
+            Quantity::new_with_strategy(2.5e6, units::SQUARE_METER, Floor),
+            Quantity::new(2.5e6, units::SQUARE_METER)
+        );
+
+        let volume = Quantity::new(3e-6, units::CUBIC_METER);
+        assert_eq!(format!("{:.0}", volume), "3000 mm³");
+
+        let side = Quantity::new(2e3, units::METER);
+        let area = &side * &side;
+        assert_eq!(area.unit, units::SQUARE_METER);
+        assert_eq!(format!("{}", area), "4 km²");
+        assert_eq!(format!("{}", &(&area * &side) / &side), "4 km²");
+        assert_eq!(format!("{}", &area / &side), "2 km");
+
+        let flow = Unit::of_dimension(Dimension::LENGTH.powi(2) / Dimension::TIME, Base::B1000);
+        assert_eq!(format!("{}", Quantity::new(2e6, flow)), "2000000 m²/s");
+
+        assert!(area.checked_add(&side).is_err());
+        assert_eq!(format!("{}", area.checked_add(&area).unwrap()), "8 km²");
+    }
+
+    #[test]
+    fn parse_powered_units() {
+        let actual = units::SQUARE_METER.parse("2.5 km²").unwrap();
+        assert_eq!(actual.to_f64(), 2.5e6);
+
+        let actual = units::CUBIC_METER.parse("2 mm³").unwrap();
+        assert!((actual.to_f64() - 2e-9).abs() < 1e-20);
+
+        assert!(units::SQUARE_METER.parse("2.5 km").is_err());
+    }
+
+    #[test]
+    fn units_with_unit_prefix() {
+        let mass = Quantity::new(1.5e-3, units::KILOGRAM);
+        assert_eq!(format!("{}", mass), "1.5 g");
+        assert_eq!(mass.to_f64(), 1.5e-3);
+        assert_eq!(format!("{:#}", mass), "1.5 grams");
+
+        assert_eq!(format!("{}", Quantity::new(1, units::KILOGRAM)), "1 kg");
+        assert_eq!(units::KILOGRAM.prefixed_symbol(), "kg");
+        assert_eq!(units::SQUARE_METER.prefixed_symbol(), "m²");
+        assert_eq!(
+            format!("{}", Quantity::new(2300, units::KILOGRAM)),
+            "2.3 Mg"
+        );
+        assert_eq!(format!("{}", Quantity::new(2300, units::TONNE)), "2.3 t");
+        assert_eq!(format!("{}", Quantity::new(4.5e6, units::TONNE)), "4.5 kt");
+        assert_eq!(format!("{}", Quantity::new(150, units::TONNE)), "0.15 t");
+
+        let actual = units::KILOGRAM.parse("1.5 mg").unwrap();
+        assert!((actual.to_f64() - 1.5e-6).abs() < 1e-18);
+        let actual = units::TONNE.parse("2.3 kt").unwrap();
+        assert_eq!(actual.to_f64(), 2.3e6);
+
+        // Kilograms are the coherent unit of mass in dimensional arithmetic.
+        let force = Quantity::new(2, units::KILOGRAM) * Quantity::new(3, units::METER)
+            / Quantity::new(1, units::SECOND)
+            / Quantity::new(1, units::SECOND);
+        assert_eq!(force.unit, units::NEWTON);
+        assert_eq!(format!("{}", force), "6 N");
+        let weight = &Quantity::new(9.81, units::NEWTON) / &Quantity::new(9.81, units::METER);
+        assert_eq!(format!("{}", weight), "1 kg/s²");
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert!(matches!(
+            units::SECOND.parse("1.3 µB"),
+            Err(SIUnitsError::UnitParsing(_))
+        ));
+        assert!(units::SECOND.parse("s").is_err());
+        assert!(units::SECOND.parse("abc s").is_err());
+        assert!(units::BYTE.parse("1.3 XB").is_err());
+    }
+
+    #[test]
+    fn quantities_from_durations() {
+        let quantity = Quantity::from(Duration::from_micros(1_234));
+        assert_eq!(quantity.value.mantissa, 1.234);
+        assert_eq!(format!("{}", quantity), "1.234 ms");
+        assert_eq!(
+            format!("{}", Quantity::from(Duration::from_secs(5400))),
+            "5400 s"
+        );
+        assert_eq!(format!("{}", Quantity::from(Duration::ZERO)), "0 s");
+    }
+
+    #[test]
+    fn quantity_breakdowns() {
+        let join = |quantities: Vec<Quantity>| -> String {
+            let parts: Vec<String> = quantities.iter().map(ToString::to_string).collect();
+            parts.join(" ")
+        };
+
+        let size = Quantity::new(1_234_567_890, units::BYTE);
+        assert_eq!(join(size.breakdown(3)), "1 GB 234 MB 567 kB");
+        assert_eq!(join(size.breakdown(2)), "1 GB 234 MB");
+
+        let area = Quantity::new(2_500_000, units::SQUARE_METER);
+        assert_eq!(join(area.breakdown(2)), "2 km² 500000 m²");
+
+        let mass = Quantity::new(1.5, units::KILOGRAM);
+        assert_eq!(join(mass.breakdown(2)), "1 kg 500 g");
+
+        let length = Quantity::new(2.3, units::METER);
+        assert_eq!(join(length.breakdown(3)), "2 m 300 mm");
+        let elapsed = Quantity::new(4.35e-3, units::SECOND);
+        assert_eq!(join(elapsed.breakdown(3)), "4 ms 350 µs");
+    }
+}
@@ -0,0 +1,509 @@
+//! Rates such as throughputs (`12.3 MB/s`), latencies per operation
+//! (`45 µs/op`) or operation rates (`22 kop/s`).
+//!
+//! A [`Rate`] divides a numerator [`Quantity`] by a denominator, which is
+//! usually a [`Duration`] or a count. It is displayed either in the
+//! [`Form::Direct`] form, numerator per denominator, or in the
+//! [`Form::Inverse`] form, denominator per numerator, each side with its
+//! own unit, so that the prefix goes on the side which needs it.
+//!
+//! # Example
+//!
+//! ```
+//! use std::time::Duration;
+//! use si_scale::quantity::{units, Quantity};
+//! use si_scale::rate::{Form, Rate};
+//!
+//! let transferred = Quantity::new(24.6e6, units::BYTE);
+//! let rate = Rate::per_duration(transferred, Duration::from_secs(2));
+//! assert_eq!(format!("{:.1}", rate), "12.3 MB/s");
+//!
+//! let elapsed = Quantity::new(0.09, units::SECOND);
+//! let rate = Rate::per_count(elapsed, 2000, units::OPERATION);
+//! assert_eq!(format!("{}", rate), "45 µs/op");
+//! assert_eq!(format!("{:.1}", rate.form(Form::Inverse)), "22.2 kop/s");
+//! ```
+//!
+//! Rates written as `"10 MB/s"`, `"2 Gbit/s"` or `"1.5 kreq/min"` are
+//! parsed with [`parse_rate()`]:
+//!
+//! ```
+//! use si_scale::rate::parse_rate;
+//!
+//! let rate = parse_rate("1.5 kreq/min").unwrap();
+//! assert_eq!(rate.value, 25.0);
+//! assert_eq!(rate.unit, "req");
+//! assert_eq!(rate.per, "s");
+//! ```
+
+use alloc::format;
+use alloc::string::{String, ToString};
+use core::fmt;
+use core::str::FromStr;
+use core::time::Duration;
+
+use crate::base::Base;
+use crate::mass::PREFIXED_TONNES;
+use crate::prefix::{Constraint, Prefix};
+use crate::quantity::{units, Quantity, Unit};
+use crate::value::{IntoF64, Value};
+use crate::{Result, SIUnitsError};
+
+/// Selects which side of a [`Rate`] is displayed as the numerator.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Form {
+    /// Selects the direct form, unless only the inverse form can be
+    /// displayed with a mantissa between `1` and `1000` (in base 1000)
+    /// within the prefix constraint of its unit.
+    #[default]
+    Auto,
+    /// Numerator per denominator, as in `12.3 MB/s` or `45 µs/op`.
+    Direct,
+    /// Denominator per numerator, as in `22 kop/s` for a latency of
+    /// `45 µs/op`.
+    Inverse,
+}
+
+/// A numerator quantity divided by a denominator quantity.
+///
+/// The `Display` implementation writes the mantissa with `"{}"`, unless a
+/// precision is provided to the formatter, as in `"{:.1}"`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Rate {
+    numerator: Quantity,
+    denominator: Quantity,
+    form: Form,
+}
+
+impl Rate {
+    /// Returns the rate of `numerator` per `denominator`.
+    pub fn new(numerator: Quantity, denominator: Quantity) -> Self {
+        Rate {
+            numerator,
+            denominator,
+            form: Form::Auto,
+        }
+    }
+
+    /// Returns the rate of `numerator` per `duration`, displayed per second.
+    ///
+    /// The duration is converted from its integer number of nanoseconds,
+    /// as with `Quantity::from(duration)`.
+    pub fn per_duration(numerator: Quantity, duration: Duration) -> Self {
+        Self::new(numerator, Quantity::from(duration))
+    }
+
+    /// Returns the rate of `numerator` per `count` of `unit`, such as
+    /// [`units::OPERATION`].
+    pub fn per_count<F>(numerator: Quantity, count: F, unit: Unit) -> Self
+    where
+        F: IntoF64,
+    {
+        Self::new(numerator, Quantity::new(count, unit))
+    }
+
+    /// Returns self displayed in the provided `form`.
+    pub fn form(self, form: Form) -> Self {
+        Rate { form, ..self }
+    }
+
+    /// Returns the numerator divided by the denominator, each expressed as
+    /// with [`Quantity::to_f64()`], such as bytes per second or meters per
+    /// kilogram.
+    pub fn to_f64(&self) -> f64 {
+        self.numerator.to_f64() / self.denominator.to_f64()
+    }
+
+    /// Returns the scaled numerator of the selected form, and the unit of
+    /// its denominator.
+    ///
+    /// Infinite, NaN and zero rates, such as a rate per zero duration, are
+    /// not scaled and are in the direct form unless `Form::Inverse` is
+    /// selected.
+    pub fn parts(&self) -> (Quantity, &Unit) {
+        let rate = self.to_f64();
+        let direct = || (scaled(rate, &self.numerator.unit), &self.denominator.unit);
+        let inverse = || {
+            (
+                scaled(1.0 / rate, &self.denominator.unit),
+                &self.numerator.unit,
+            )
+        };
+        match self.form {
+            Form::Direct => direct(),
+            Form::Inverse => inverse(),
+            Form::Auto => {
+                if !rate.is_finite() || rate == 0.0 {
+                    return direct();
+                }
+                let direct = direct();
+                if is_well_scaled(&direct.0) {
+                    return direct;
+                }
+                let inverse = inverse();
+                if is_well_scaled(&inverse.0) {
+                    inverse
+                } else {
+                    direct
+                }
+            }
+        }
+    }
+}
+
+/// Returns the quantity of `x` in `unit`, which is not scaled if `x` is
+/// infinite or NaN.
+fn scaled(x: f64, unit: &Unit) -> Quantity {
+    if x.is_finite() {
+        return Quantity::new(x, unit.clone());
+    }
+    Quantity {
+        value: Value {
+            mantissa: x,
+            prefix: Prefix::Unit,
+            base: unit.base(),
+        },
+        unit: unit.clone(),
+    }
+}
+
+/// Returns `true` if the prefix of the finite and non-zero `quantity` is not
+/// clamped by the constraint of its unit, i.e. its mantissa is between `1`
+/// and `1000` (in base 1000).
+fn is_well_scaled(quantity: &Quantity) -> bool {
+    let x = quantity.value.to_f64();
+    let unconstrained = Value::new_with(x, quantity.value.base, Constraint::None);
+    unconstrained.prefix == quantity.value.prefix
+}
+
+impl fmt::Display for Rate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (quantity, per) = self.parts();
+        match f.precision() {
+            Some(precision) => write!(f, "{:.*}", precision, quantity)?,
+            None => write!(f, "{}", quantity)?,
+        }
+        write!(f, "/{}", per.prefixed_symbol())
+    }
+}
+
+/// A rate parsed by [`parse_rate()`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParsedRate {
+    /// Amount of the numerator unit, without prefix, per second if the
+    /// denominator is a time unit, or per single denominator unit otherwise.
+    pub value: f64,
+    /// Symbol of the numerator unit, without prefix, such as `"B"`.
+    pub unit: String,
+    /// Symbol of the denominator unit, without prefix: `"s"` for time units,
+    /// or the counted unit, such as `"op"`.
+    pub per: String,
+}
+
+/// Time units accepted as denominators besides the (prefixed) second, with
+/// their duration in seconds.
+const TIME_UNITS: [(&str, f64); 3] = [("min", 60.0), ("h", 3600.0), ("d", 86400.0)];
+
+/// Parses a rate such as `"10 MB/s"`, `"2 Gbit/s"`, `"1.5 kreq/min"` or
+/// `"500 µs/op"`.
+///
+/// Both sides of the slash are a unit symbol, optionally preceded by a
+/// prefix as accepted by [`Prefix::from_str()`] (`k`, `M`, `kilo`, ...), by
+/// `u` for `µ`, or by a binary prefix (`Ki`, `Mi`, ...). Time denominators
+/// (`s`, `ms`, `min`, `h` or `d`) are normalized to seconds, other
+/// denominators are counts, normalized to a single unit.
+///
+/// A prefix is only recognized in front of a known unit symbol, such as
+/// `B`, `bit`, `byte`, `s`, `m`, `op` or `req`, and only if the whole symbol
+/// is not itself a known unit: `"mol"` stays moles. Other symbols, such as
+/// `msg` or `pages`, are kept as they are, without prefix.
+///
+/// # Example
+///
+/// ```
+/// use si_scale::rate::parse_rate;
+///
+/// assert_eq!(parse_rate("10 MB/s").unwrap().value, 10e6);
+/// assert_eq!(parse_rate("2 Gbit/s").unwrap().value, 2e9);
+/// assert_eq!(parse_rate("4 KiB/ms").unwrap().value, 4_096_000.0);
+///
+/// let latency = parse_rate("500 µs/op").unwrap();
+/// assert_eq!((latency.value, latency.unit.as_str()), (0.0005, "s"));
+/// assert_eq!(latency.per, "op");
+///
+/// let messages = parse_rate("1000 msg/s").unwrap();
+/// assert_eq!((messages.value, messages.unit.as_str()), (1000.0, "msg"));
+/// ```
+///
+/// # Errors
+///
+/// Returns [`SIUnitsError::UnitParsing`] if the string does not contain a
+/// number, a unit, a slash and a denominator unit.
+pub fn parse_rate(s: &str) -> Result<ParsedRate> {
+    let error = || SIUnitsError::UnitParsing(format!("cannot parse {:?} as a rate", s));
+
+    let (numerator, denominator) = s.split_once('/').ok_or_else(error)?;
+    let (number, numerator) = split_number(numerator.trim()).ok_or_else(error)?;
+    let denominator = denominator.trim();
+
+    let (base, prefix, unit) = split_prefix(numerator);
+    let value = scale(number, base, prefix.exponent());
+
+    let (value, per) = match TIME_UNITS.iter().find(|(symbol, _)| *symbol == denominator) {
+        Some((_, seconds)) => (value / seconds, "s"),
+        None => {
+            let (base, prefix, per) = split_prefix(denominator);
+            (scale(value, base, -prefix.exponent()), per)
+        }
+    };
+    if unit.is_empty() || per.is_empty() || unit.contains(char::is_whitespace) {
+        return Err(error());
+    }
+
+    Ok(ParsedRate {
+        value,
+        unit: unit.to_string(),
+        per: per.to_string(),
+    })
+}
+
+/// Splits `s` into its leading number, which may contain `_` separators,
+/// and the rest, or returns `None` if it does not start with a number.
+fn split_number(s: &str) -> Option<(f64, &str)> {
+    s.char_indices()
+        .map(|(index, _)| index)
+        .chain(core::iter::once(s.len()))
+        .rev()
+        .find_map(|index| {
+            let (number, rest) = s.split_at(index);
+            let number: f64 = number.trim_end().replace('_', "").parse().ok()?;
+            Some((number, rest.trim_start()))
+        })
+}
+
+/// Unit symbols after which a prefix is recognized, besides the symbols of
+/// the predefined units.
+const OTHER_UNITS: [&str; 7] = ["op", "byte", "req", "A", "K", "mol", "V"];
+
+/// Returns `true` if `symbol` is the symbol of a predefined unit or one of
+/// [`OTHER_UNITS`].
+fn is_known_unit(symbol: &str) -> bool {
+    units::PREDEFINED
+        .iter()
+        .map(Unit::symbol)
+        .chain(OTHER_UNITS)
+        .any(|known| known == symbol)
+}
+
+/// Splits `symbol` into its base, prefix and unit symbol, trying the
+/// longest prefix first. The symbol is only split if the rest is a known
+/// unit and the whole symbol is not: `mm` is split, but `m`, `mol` and `msg`
+/// are unit symbols. Tonnes are only split from [`PREFIXED_TONNES`].
+fn split_prefix(symbol: &str) -> (Base, Prefix, &str) {
+    let unprefixed = (Base::B1000, Prefix::Unit, symbol);
+    if is_known_unit(symbol) {
+        return unprefixed;
+    }
+    if let Some((_, prefix)) = PREFIXED_TONNES.iter().find(|(known, _)| *known == symbol) {
+        return (Base::B1000, *prefix, "t");
+    }
+    symbol
+        .char_indices()
+        .map(|(index, _)| index)
+        .rev()
+        .filter(|&index| index > 0)
+        .find_map(|index| {
+            let (head, unit) = symbol.split_at(index);
+            if !is_known_unit(unit) {
+                return None;
+            }
+            let (base, head) = match head.strip_suffix('i') {
+                Some("K") => (Base::B1024, "k"),
+                Some(head) if !head.is_empty() => (Base::B1024, head),
+                _ => (Base::B1000, head),
+            };
+            let head = if head == "u" { "µ" } else { head };
+            match Prefix::from_str(head) {
+                Ok(prefix) if base == Base::B1024 && prefix < Prefix::Kilo => None,
+                Ok(prefix) => Some((base, prefix, unit)),
+                Err(_) => None,
+            }
+        })
+        .unwrap_or(unprefixed)
+}
+
+/// Returns `x` multiplied by `base` raised to `exponent`, dividing by the
+/// positive power for negative exponents, which is exact more often.
+fn scale(x: f64, base: Base, exponent: i32) -> f64 {
+    if exponent < 0 {
+        x / base.pow(-exponent)
+    } else {
+        x * base.pow(exponent)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn throughputs() {
+        let rate = Rate::per_duration(Quantity::new(24.6e6, units::BYTE), Duration::from_secs(2));
+        assert_eq!(rate.to_f64(), 12.3e6);
+        assert_eq!(format!("{:.1}", rate), "12.3 MB/s");
+
+        let rate = Rate::per_duration(
+            Quantity::new(3 * 1024 * 1024, units::BIBYTE),
+            Duration::from_millis(500),
+        );
+        assert_eq!(format!("{}", rate), "6 MiB/s");
+
+        // Below 1 B/s, the inverse form is better.
+        let rate = Rate::per_duration(Quantity::new(1, units::BYTE), Duration::from_secs(4));
+        assert_eq!(format!("{}", rate), "4 s/B");
+        assert_eq!(format!("{}", rate.form(Form::Direct)), "0.25 B/s");
+
+        let duration = Duration::new(1, 123_456_789);
+        let bytes = Quantity::new(1e9, units::BYTE);
+        assert_eq!(
+            Rate::per_duration(bytes.clone(), duration),
+            Rate::new(bytes, Quantity::from(duration))
+        );
+    }
+
+    #[test]
+    fn operations() {
+        let ops = Quantity::new(44_000, units::OPERATION);
+        let rate = Rate::per_duration(ops, Duration::from_secs(2));
+        assert_eq!(format!("{}", rate), "22 kop/s");
+        assert_eq!(format!("{:.1}", rate.form(Form::Inverse)), "45.5 µs/op");
+
+        let ops = Quantity::new(1, units::OPERATION);
+        let rate = Rate::per_duration(ops, Duration::from_secs(2));
+        assert_eq!(format!("{}", rate), "2 s/op");
+    }
+
+    #[test]
+    fn latencies() {
+        let elapsed = Quantity::new(0.09, units::SECOND);
+        let rate = Rate::per_count(elapsed, 2000, units::OPERATION);
+        assert_eq!(format!("{}", rate), "45 µs/op");
+        assert_eq!(
+            format!("{:.1}", rate.clone().form(Form::Inverse)),
+            "22.2 kop/s"
+        );
+        assert_eq!(format!("{:.1}", rate.form(Form::Direct)), "45.0 µs/op");
+
+        // Neither form is well scaled: the direct form is kept.
+        let elapsed = Quantity::new(5000, units::SECOND);
+        let rate = Rate::per_count(elapsed, 1, units::OPERATION);
+        assert_eq!(format!("{}", rate), "5000 s/op");
+    }
+
+    #[test]
+    fn zero_rates() {
+        let rate = Rate::per_duration(Quantity::new(0, units::BYTE), Duration::from_secs(1));
+        assert_eq!(format!("{}", rate), "0 B/s");
+    }
+
+    #[test]
+    fn zero_durations() {
+        let rate = Rate::per_duration(Quantity::new(1, units::BYTE), Duration::ZERO);
+        assert_eq!(rate.to_f64(), f64::INFINITY);
+        assert_eq!(format!("{}", rate), "inf B/s");
+        assert_eq!(format!("{}", rate.clone().form(Form::Direct)), "inf B/s");
+        assert_eq!(format!("{}", rate.form(Form::Inverse)), "0 s/B");
+
+        let rate = Rate::per_duration(Quantity::new(0, units::BYTE), Duration::ZERO);
+        assert_eq!(format!("{}", rate), "NaN B/s");
+
+        let rate = Rate::per_count(Quantity::new(0, units::BYTE), 1, units::OPERATION);
+        assert_eq!(format!("{}", rate.form(Form::Inverse)), "inf op/B");
+    }
+
+    #[test]
+    fn prefixed_denominators() {
+        let distance = Quantity::new(10, units::METER);
+        let rate = Rate::new(distance, Quantity::new(2, units::KILOGRAM));
+        assert_eq!(rate.to_f64(), 5.0);
+        assert_eq!(format!("{}", rate), "5 m/kg");
+        assert_eq!(format!("{}", rate.form(Form::Inverse)), "200 g/m");
+    }
+
+    #[test]
+    fn parse_rates() {
+        let rate = parse_rate("10 MB/s").unwrap();
+        assert_eq!(
+            rate,
+            ParsedRate {
+                value: 10e6,
+                unit: "B".to_string(),
+                per: "s".to_string()
+            }
+        );
+        assert_eq!(parse_rate("2 Gbit/s").unwrap().value, 2e9);
+        assert_eq!(parse_rate("2 Gbit/s").unwrap().unit, "bit");
+        assert_eq!(parse_rate("1.5 kreq/min").unwrap().value, 25.0);
+        assert_eq!(parse_rate("7_200 B/h").unwrap().value, 2.0);
+        assert_eq!(parse_rate("3 kilobyte/ms").unwrap().value, 3e6);
+        assert_eq!(parse_rate("3 MiB/s").unwrap().value, 3.0 * 1024.0 * 1024.0);
+        assert_eq!(parse_rate("1 kiB/s").unwrap().value, 1024.0);
+        assert_eq!(parse_rate("2m/s").unwrap().unit, "m");
+        assert_eq!(parse_rate("86.4 km/d").unwrap().value, 1.0);
+    }
+
+    #[test]
+    fn parse_count_rates() {
+        let rate = parse_rate("500 µs/op").unwrap();
+        assert_eq!(rate.value, 0.0005);
+        assert_eq!(rate.unit, "s");
+        assert_eq!(rate.per, "op");
+        assert_eq!(parse_rate("500 us/op").unwrap(), rate);
+        assert_eq!(parse_rate("3 ms/kop").unwrap().value, 3e-6);
+    }
+
+    #[test]
+    fn parse_unprefixed_symbols() {
+        let rate = parse_rate("1000 msg/s").unwrap();
+        assert_eq!((rate.value, rate.unit.as_str()), (1000.0, "msg"));
+        let rate = parse_rate("10 pages/s").unwrap();
+        assert_eq!((rate.value, rate.unit.as_str()), (10.0, "pages"));
+        let rate = parse_rate("20 packets/s").unwrap();
+        assert_eq!((rate.value, rate.unit.as_str()), (20.0, "packets"));
+        let rate = parse_rate("3 mol/s").unwrap();
+        assert_eq!((rate.value, rate.unit.as_str()), (3.0, "mol"));
+        let rate = parse_rate("3 mmol/s").unwrap();
+        assert_eq!((rate.value, rate.unit.as_str()), (3e-3, "mol"));
+
+        let rate = parse_rate("500 µs/page").unwrap();
+        assert_eq!((rate.value, rate.per.as_str()), (0.0005, "page"));
+        let rate = parse_rate("2 kreq/Mmsg").unwrap();
+        assert_eq!((rate.value, rate.per.as_str()), (2e3, "Mmsg"));
+    }
+
+    #[test]
+    fn parse_tonnes() {
+        let rate = parse_rate("10 ft/s").unwrap();
+        assert_eq!((rate.value, rate.unit.as_str()), (10.0, "ft"));
+        let rate = parse_rate("3 pt/s").unwrap();
+        assert_eq!((rate.value, rate.unit.as_str()), (3.0, "pt"));
+        let rate = parse_rate("2 ct/s").unwrap();
+        assert_eq!((rate.value, rate.unit.as_str()), (2.0, "ct"));
+
+        let rate = parse_rate("2 t/s").unwrap();
+        assert_eq!((rate.value, rate.unit.as_str()), (2.0, "t"));
+        let rate = parse_rate("2 kt/h").unwrap();
+        assert_eq!((rate.value * 3600.0, rate.unit.as_str()), (2e3, "t"));
+        let rate = parse_rate("1.5 Gt/s").unwrap();
+        assert_eq!((rate.value, rate.unit.as_str()), (1.5e9, "t"));
+    }
+
+    #[test]
+    fn parse_invalid_rates() {
+        assert!(parse_rate("10 MB").is_err());
+        assert!(parse_rate("MB/s").is_err());
+        assert!(parse_rate("10 /s").is_err());
+        assert!(parse_rate("10 MB/").is_err());
+        assert!(parse_rate("10 M B/s").is_err());
+    }
+}
@@ -0,0 +1,231 @@
+//! Strategies selecting the prefix of a value.
+//!
+//! By default, a [`Value`][`crate::value::Value`] uses the largest allowed
+//! prefix whose exponent is lower than or equal to the integral exponent of
+//! the number (see [`Floor`]), so that mantissas fall in `[1, 1000)`. Other
+//! strategies can be provided to
+//! [`Value::new_with_strategy()`][`crate::value::Value::new_with_strategy()`]
+//! and to the [`scale_fn!()`][`crate::scale_fn`] macro.
+//!
+//! # Example
+//!
+//! ```
+//! use si_scale::prelude::{Base, Constraint, Value};
+//! use si_scale::strategy::{Floor, Shortest, Threshold};
+//! use si_scale::format_value;
+//!
+//! let x = 950_000;
+//!
+//! let v = Value::new_with_strategy(x, Base::B1000, Constraint::None, Floor);
+//! assert_eq!(format!("{}B", format_value!(v, "{}")), "950 kB");
+//!
+//! let v = Value::new_with_strategy(x, Base::B1000, Constraint::None, Threshold(0.9));
+//! assert_eq!(format!("{}B", format_value!(v, "{}")), "0.95 MB");
+//!
+//! let v = Value::new_with_strategy(1.2e6, Base::B1000, Constraint::None, Shortest);
+//! assert_eq!(format!("{}B", format_value!(v, "{}")), "1.2 MB");
+//! ```
+
+use core::fmt::{self, Write};
+
+use crate::base::Base;
+use crate::math;
+use crate::prefix::{Constraint, Prefix};
+use crate::value::Value;
+
+/// Selects the prefix used to represent a number.
+pub trait Strategy {
+    /// Returns the prefix used to represent `x` in `base`, respecting the
+    /// `constraint`.
+    fn select(&self, x: f64, base: &Base, constraint: &Constraint) -> Prefix;
+}
+
+impl<S: Strategy + ?Sized> Strategy for &S {
+    fn select(&self, x: f64, base: &Base, constraint: &Constraint) -> Prefix {
+        (**self).select(x, base, constraint)
+    }
+}
+
+/// The default strategy: selects the largest allowed prefix whose exponent
+/// is lower than or equal to the integral exponent of the number, as in
+/// `950 kB` or `1.2 MB`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Floor;
+
+impl Strategy for Floor {
+    fn select(&self, x: f64, base: &Base, constraint: &Constraint) -> Prefix {
+        let exponent = base.integral_exponent_for(x);
+        Value::closest_prefix_for(exponent, constraint)
+    }
+}
+
+/// Selects the next larger prefix as soon as the mantissa expressed with it
+/// reaches the threshold.
+///
+/// For instance, with `Threshold(0.9)`, `950_000` is represented as `0.95 M`
+/// instead of `950 k`, but `850_000` remains `850 k`. A threshold of `1.0`
+/// is equivalent to [`Floor`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Threshold(pub f64);
+
+impl Strategy for Threshold {
+    fn select(&self, x: f64, base: &Base, constraint: &Constraint) -> Prefix {
+        let floor = Floor.select(x, base, constraint);
+        let next = next_prefix(floor, 1, constraint);
+        if next != floor && math::abs(x) / base.pow(next.exponent()) >= self.0 {
+            next
+        } else {
+            floor
+        }
+    }
+}
+
+/// Selects, among the [`Floor`] prefix and its allowed neighbors, the one
+/// yielding the shortest mantissa when displayed with `"{}"`, such as
+/// `950 k` rather than `0.95 M`, but `1.2 M` rather than `1200 k`.
+///
+/// Ties are resolved in favor of the `Floor` prefix.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Shortest;
+
+impl Strategy for Shortest {
+    fn select(&self, x: f64, base: &Base, constraint: &Constraint) -> Prefix {
+        let floor = Floor.select(x, base, constraint);
+        let length = |prefix: Prefix| {
+            let mut length = Length(0);
+            let _ = write!(length, "{}", x / base.pow(prefix.exponent()));
+            length.0
+        };
+
+        [
+            next_prefix(floor, -1, constraint),
+            next_prefix(floor, 1, constraint),
+        ]
+        .iter()
+        .fold((floor, length(floor)), |(best, best_length), &prefix| {
+            let prefix_length = length(prefix);
+            if prefix_length < best_length {
+                (prefix, prefix_length)
+            } else {
+                (best, best_length)
+            }
+        })
+        .0
+    }
+}
+
+/// A `fmt::Write` sink which only counts the written bytes.
+struct Length(usize);
+
+impl Write for Length {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.0 += s.len();
+        Ok(())
+    }
+}
+
+/// Returns the closest allowed prefix above (`direction` = 1) or below
+/// (`direction` = -1) the provided `prefix`, or `prefix` itself if there is
+/// none.
+pub(crate) fn next_prefix(prefix: Prefix, direction: i32, constraint: &Constraint) -> Prefix {
+    let mut exponent = prefix.exponent() + 3 * direction;
+    while (Prefix::Yocto as i32..=Prefix::Yotta as i32).contains(&exponent) {
+        // The closest prefix is the largest allowed one below the exponent,
+        // so that skipping over gaps is only needed when going up.
+        let candidate = Value::closest_prefix_for(exponent, constraint);
+        if (candidate.exponent() - prefix.exponent()) * direction > 0 {
+            return candidate;
+        }
+        if direction < 0 {
+            break;
+        }
+        exponent += 3;
+    }
+    prefix
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prefix::PrefixSet;
+
+    #[test]
+    fn floor_strategy() {
+        let actual = Floor.select(950_000.0, &Base::B1000, &Constraint::None);
+        assert_eq!(actual, Prefix::Kilo);
+
+        let actual = Floor.select(1.5e-5, &Base::B1000, &Constraint::UnitAndAbove);
+        assert_eq!(actual, Prefix::Unit);
+    }
+
+    #[test]
+    fn threshold_strategy() {
+        let strategy = Threshold(0.9);
+
+        let actual = strategy.select(950_000.0, &Base::B1000, &Constraint::None);
+        assert_eq!(actual, Prefix::Mega);
+
+        let actual = strategy.select(-950_000.0, &Base::B1000, &Constraint::None);
+        assert_eq!(actual, Prefix::Mega);
+
+        let actual = strategy.select(850_000.0, &Base::B1000, &Constraint::None);
+        assert_eq!(actual, Prefix::Kilo);
+
+        let actual = strategy.select(0.95, &Base::B1000, &Constraint::UnitAndBelow);
+        assert_eq!(actual, Prefix::Unit);
+
+        // The next prefix must be allowed by the constraint.
+        let actual = strategy.select(950.0, &Base::B1000, &Constraint::UnitAndBelow);
+        assert_eq!(actual, Prefix::Unit);
+
+        let actual = strategy.select(1000.0 * 1000.0, &Base::B1024, &Constraint::None);
+        assert_eq!(actual, Prefix::Mega);
+
+        let actual = Threshold(1.0).select(999_999.0, &Base::B1000, &Constraint::None);
+        assert_eq!(actual, Prefix::Kilo);
+
+        // The next prefix skips over those which are not allowed.
+        let constraint = Constraint::Set(PrefixSet::single(Prefix::Unit).with(Prefix::Mega));
+        let actual = strategy.select(0.95e6, &Base::B1000, &constraint);
+        assert_eq!(actual, Prefix::Mega);
+        let actual = strategy.select(0.85e6, &Base::B1000, &constraint);
+        assert_eq!(actual, Prefix::Unit);
+    }
+
+    #[test]
+    fn shortest_strategy() {
+        let actual = Shortest.select(950_000.0, &Base::B1000, &Constraint::None);
+        assert_eq!(actual, Prefix::Kilo);
+
+        let actual = Shortest.select(1.2e6, &Base::B1000, &Constraint::None);
+        assert_eq!(actual, Prefix::Mega);
+
+        let actual = Shortest.select(0.5, &Base::B1000, &Constraint::None);
+        assert_eq!(actual, Prefix::Milli);
+
+        let actual = Shortest.select(1.2e6, &Base::B1000, &Constraint::UnitOnly);
+        assert_eq!(actual, Prefix::Unit);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn next_prefixes() {
+        let constraint = Constraint::Custom(vec![Prefix::Unit, Prefix::Mega]);
+        assert_eq!(next_prefix(Prefix::Unit, 1, &constraint), Prefix::Mega);
+        assert_eq!(next_prefix(Prefix::Mega, 1, &constraint), Prefix::Mega);
+        assert_eq!(next_prefix(Prefix::Mega, -1, &constraint), Prefix::Unit);
+        assert_eq!(next_prefix(Prefix::Unit, -1, &constraint), Prefix::Unit);
+        assert_eq!(
+            next_prefix(Prefix::Kilo, 1, &Constraint::None),
+            Prefix::Mega
+        );
+        assert_eq!(
+            next_prefix(Prefix::Kilo, -1, &Constraint::None),
+            Prefix::Unit
+        );
+        assert_eq!(
+            next_prefix(Prefix::Yotta, 1, &Constraint::None),
+            Prefix::Yotta
+        );
+    }
+}
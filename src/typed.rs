@@ -0,0 +1,378 @@
+//! Strongly-typed quantities, preventing unit mix-ups such as printing a
+//! byte count as seconds.
+//!
+//! Each type wraps a `f64` expressed in its unit without prefix, is
+//! displayed like the corresponding helper function (e.g. [`Seconds`] like
+//! [`seconds()`][`crate::helpers::seconds()`]), and only supports
+//! arithmetic yielding meaningful units: adding seconds to seconds, dividing
+//! bytes by seconds, but not adding bytes to seconds.
+//!
+//! These types do not implement [`IntoF64`], so they cannot be passed to
+//! the helper function of another unit by mistake. Their number is private:
+//! a typed quantity is only built from a plain number with `new()`, and the
+//! number is only read back with `value()`.
+//!
+//! # Example
+//!
+//! ```
+//! use si_scale::typed::{Bytes, Hertz, Seconds};
+//!
+//! let size = Bytes::new(3_000_000);
+//! let elapsed = Seconds::new(1.5) + Seconds::new(0.5);
+//! assert_eq!(format!("{}", elapsed), "2 s");
+//! assert_eq!(format!("{}", size / elapsed), "1.5 MB/s");
+//! assert_eq!(format!("{:.1}", 1.0 / Seconds::new(4e-9)), "250.0 MHz");
+//! ```
+//!
+//! Adding bytes to seconds does not compile:
+//!
+//! ```compile_fail
+//! use si_scale::typed::{Bytes, Seconds};
+//!
+//! let oops = Bytes::new(16) + Seconds::new(1);
+//! ```
+//!
+//! Nor does converting the number of bytes into seconds:
+//!
+//! ```compile_fail
+//! use si_scale::typed::{Bytes, Seconds};
+//!
+//! let oops: Seconds = Bytes::new(16).value().into();
+//! ```
+
+use alloc::format;
+use alloc::string::ToString;
+use core::fmt;
+use core::iter::Sum;
+use core::ops::{Add, AddAssign, Div, Mul, Neg, Sub, SubAssign};
+
+use crate::base::Base;
+use crate::buffer::pad_with;
+use crate::dimension::Dimension;
+use crate::quantity::{units, Quantity, Unit};
+use crate::value::IntoF64;
+
+/// Converts the operands and results of cross-unit arithmetic from and to
+/// `f64`, without exposing a public conversion between units.
+trait Raw {
+    fn from_raw(x: f64) -> Self;
+    fn raw(self) -> f64;
+}
+
+impl Raw for f64 {
+    fn from_raw(x: f64) -> Self {
+        x
+    }
+
+    fn raw(self) -> f64 {
+        self
+    }
+}
+
+/// Defines a typed quantity and its same-unit arithmetic.
+macro_rules! typed_quantity {
+    ($(#[$doc:meta])* $name:ident, unit: $unit:expr) => {
+        $(#[$doc])*
+        #[derive(Debug, Clone, Copy, Default, PartialEq, PartialOrd)]
+        pub struct $name(f64);
+
+        impl $name {
+            /// Returns a new typed quantity of `x`, expressed in the unit
+            /// without prefix.
+            pub fn new<F>(x: F) -> Self
+            where
+                F: IntoF64,
+            {
+                $name(x.into_f64())
+            }
+
+            /// Returns the number, expressed in the unit without prefix.
+            pub fn value(self) -> f64 {
+                self.0
+            }
+
+            /// Returns the unit of this type.
+            pub fn unit() -> Unit {
+                $unit
+            }
+
+            /// Converts self into a scaled, unit-carrying [`Quantity`].
+            pub fn to_quantity(self) -> Quantity {
+                Quantity::new(self.0, Self::unit())
+            }
+        }
+
+        impl fmt::Display for $name {
+            /// Displays the scaled quantity, such as `1.3 µs`. A precision
+            /// applies to the mantissa, and a width pads the whole output.
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                let quantity = self.to_quantity();
+                let output = match f.precision() {
+                    Some(precision) => format!("{:.*}", precision, quantity),
+                    None => quantity.to_string(),
+                };
+                pad_with(f, |w| w.write_str(&output))
+            }
+        }
+
+        impl Raw for $name {
+            fn from_raw(x: f64) -> Self {
+                $name(x)
+            }
+
+            fn raw(self) -> f64 {
+                self.0
+            }
+        }
+
+        impl From<$name> for Quantity {
+            fn from(x: $name) -> Self {
+                x.to_quantity()
+            }
+        }
+
+        impl Add for $name {
+            type Output = $name;
+
+            fn add(self, rhs: $name) -> Self::Output {
+                $name(self.0 + rhs.0)
+            }
+        }
+
+        impl AddAssign for $name {
+            fn add_assign(&mut self, rhs: $name) {
+                self.0 += rhs.0;
+            }
+        }
+
+        impl Sub for $name {
+            type Output = $name;
+
+            fn sub(self, rhs: $name) -> Self::Output {
+                $name(self.0 - rhs.0)
+            }
+        }
+
+        impl SubAssign for $name {
+            fn sub_assign(&mut self, rhs: $name) {
+                self.0 -= rhs.0;
+            }
+        }
+
+        impl Neg for $name {
+            type Output = $name;
+
+            fn neg(self) -> Self::Output {
+                $name(-self.0)
+            }
+        }
+
+        impl Mul<f64> for $name {
+            type Output = $name;
+
+            fn mul(self, rhs: f64) -> Self::Output {
+                $name(self.0 * rhs)
+            }
+        }
+
+        impl Mul<$name> for f64 {
+            type Output = $name;
+
+            fn mul(self, rhs: $name) -> Self::Output {
+                $name(self * rhs.0)
+            }
+        }
+
+        impl Div<f64> for $name {
+            type Output = $name;
+
+            fn div(self, rhs: f64) -> Self::Output {
+                $name(self.0 / rhs)
+            }
+        }
+
+        /// The ratio of two quantities of the same unit is a number.
+        impl Div for $name {
+            type Output = f64;
+
+            fn div(self, rhs: $name) -> Self::Output {
+                self.0 / rhs.0
+            }
+        }
+
+        impl Sum for $name {
+            fn sum<I: Iterator<Item = $name>>(iter: I) -> Self {
+                $name(iter.map(|x| x.0).sum())
+            }
+        }
+    };
+}
+
+/// Defines `$lhs * $rhs = $output` and `$rhs * $lhs = $output`.
+macro_rules! typed_product {
+    ($lhs:ident * $rhs:ident = $output:ty) => {
+        impl Mul<$rhs> for $lhs {
+            type Output = $output;
+
+            fn mul(self, rhs: $rhs) -> Self::Output {
+                Raw::from_raw(self.0 * rhs.0)
+            }
+        }
+
+        impl Mul<$lhs> for $rhs {
+            type Output = $output;
+
+            fn mul(self, rhs: $lhs) -> Self::Output {
+                Raw::from_raw(self.0 * rhs.0)
+            }
+        }
+    };
+}
+
+/// Defines `$lhs / $rhs = $output`.
+macro_rules! typed_quotient {
+    ($lhs:ty, $rhs:ident = $output:ident) => {
+        impl Div<$rhs> for $lhs {
+            type Output = $output;
+
+            fn div(self, rhs: $rhs) -> Self::Output {
+                $output(self.raw() / rhs.0)
+            }
+        }
+    };
+}
+
+typed_quantity!(
+    /// A duration in seconds, displayed like `1.3 µs` or `1234 s`.
+    Seconds,
+    unit: units::SECOND
+);
+
+typed_quantity!(
+    /// A length in meters, displayed like `1.2 km`.
+    Meters,
+    unit: units::METER
+);
+
+typed_quantity!(
+    /// A number of bytes in base 1000, displayed like `12.3 MB`.
+    Bytes,
+    unit: units::BYTE
+);
+
+typed_quantity!(
+    /// A number of bytes in base 1024, displayed like `11.8 MiB`.
+    Bibytes,
+    unit: units::BIBYTE
+);
+
+typed_quantity!(
+    /// A number of bits, displayed like `1.5 Mbit`.
+    Bits,
+    unit: units::BIT
+);
+
+typed_quantity!(
+    /// A frequency in hertz, displayed like `2.4 GHz`.
+    Hertz,
+    unit: units::HERTZ
+);
+
+typed_quantity!(
+    /// A throughput in bytes per second, displayed like `1.5 MB/s`.
+    BytesPerSecond,
+    unit: Unit::of_dimension(Dimension::BYTES / Dimension::TIME, Base::B1000)
+);
+
+typed_quantity!(
+    /// A throughput in bits per second, displayed like `100 Mbit/s`.
+    BitsPerSecond,
+    unit: Unit::of_dimension(Dimension::BITS / Dimension::TIME, Base::B1000)
+);
+
+typed_quantity!(
+    /// A velocity in meters per second, displayed like `340 m/s`.
+    MetersPerSecond,
+    unit: Unit::of_dimension(Dimension::VELOCITY, Base::B1000)
+);
+
+typed_quotient!(Bytes, Seconds = BytesPerSecond);
+typed_quotient!(Bytes, BytesPerSecond = Seconds);
+typed_quotient!(Bits, Seconds = BitsPerSecond);
+typed_quotient!(Bits, BitsPerSecond = Seconds);
+typed_quotient!(Meters, Seconds = MetersPerSecond);
+typed_quotient!(Meters, MetersPerSecond = Seconds);
+typed_quotient!(f64, Seconds = Hertz);
+typed_quotient!(f64, Hertz = Seconds);
+
+typed_product!(BytesPerSecond * Seconds = Bytes);
+typed_product!(BitsPerSecond * Seconds = Bits);
+typed_product!(MetersPerSecond * Seconds = Meters);
+typed_product!(Hertz * Seconds = f64);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{bibytes1, bytes, seconds};
+
+    #[test]
+    fn display_like_helpers() {
+        assert_eq!(format!("{}", Seconds::new(1.3e-6)), seconds(1.3e-6));
+        assert_eq!(format!("{}", Seconds::new(1234)), seconds(1234));
+        assert_eq!(format!("{}", Bytes::new(12_345_678)), bytes(12_345_678));
+        assert_eq!(
+            format!("{:.1}", Bibytes::new(12_345_678)),
+            bibytes1(12_345_678)
+        );
+        assert_eq!(format!("{:>8}", Hertz::new(2.4e9)), " 2.4 GHz");
+        assert_eq!(format!("{:.2}", Meters::new(1234)), "1.23 km");
+        assert_eq!(format!("{:<9.1}|", Meters::new(1234)), "1.2 km   |");
+        assert_eq!(format!("{:*^9}", Meters::new(2)), "***2 m***");
+    }
+
+    #[test]
+    fn same_unit_arithmetic() {
+        let mut total = Seconds::new(1) + Seconds::new(0.5) - Seconds::new(0.25);
+        assert_eq!(total, Seconds(1.25));
+        total += Seconds(0.75);
+        total -= Seconds(1.0);
+        assert_eq!(total, Seconds(1.0));
+        assert_eq!(-total, Seconds(-1.0));
+        assert_eq!(total * 3.0, Seconds(3.0));
+        assert_eq!(3.0 * total, Seconds(3.0));
+        assert_eq!(total / 4.0, Seconds(0.25));
+        assert_eq!(Bytes(3.0) / Bytes(2.0), 1.5);
+
+        let sizes = [Bytes(1.0), Bytes(2.0), Bytes(3.0)];
+        assert_eq!(sizes.iter().copied().sum::<Bytes>(), Bytes(6.0));
+    }
+
+    #[test]
+    fn cross_unit_arithmetic() {
+        let throughput = Bytes(3e6) / Seconds(2.0);
+        assert_eq!(throughput, BytesPerSecond(1.5e6));
+        assert_eq!(format!("{}", throughput), "1.5 MB/s");
+        assert_eq!(throughput * Seconds(4.0), Bytes(6e6));
+        assert_eq!(Seconds(4.0) * throughput, Bytes(6e6));
+        assert_eq!(Bytes(3e6) / throughput, Seconds(2.0));
+
+        let bitrate = Bits(1e8) / Seconds(1.0);
+        assert_eq!(format!("{}", bitrate), "100 Mbit/s");
+
+        let speed = Meters(680.0) / Seconds(2.0);
+        assert_eq!(format!("{}", speed), "340 m/s");
+        assert_eq!(speed * Seconds(1.0), Meters(340.0));
+
+        assert_eq!(1.0 / Seconds(1e-3), Hertz(1e3));
+        assert_eq!(1.0 / Hertz(4.0), Seconds(0.25));
+        assert_eq!(Hertz(50.0) * Seconds(2.0), 100.0);
+    }
+
+    #[test]
+    fn into_quantities() {
+        let quantity: Quantity = Seconds(1.3e-6).into();
+        assert_eq!(quantity, Quantity::new(1.3e-6, units::SECOND));
+        assert_eq!(Bytes::unit(), units::BYTE);
+        assert_eq!(Bytes(2.0).value(), 2.0);
+    }
+}
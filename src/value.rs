@@ -43,11 +43,21 @@
 //! ```
 
 use crate::prefix::Prefix;
-use std::convert::From;
-use std::fmt;
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+use core::convert::From;
+use core::fmt;
+use core::time::Duration;
 
 use crate::base::Base;
+use crate::buffer::BufferFormat;
+use crate::math;
 use crate::prefix::Constraint;
+#[cfg(feature = "alloc")]
+use crate::strategy::next_prefix;
+use crate::strategy::{Floor, Strategy};
+#[cfg(feature = "alloc")]
+use crate::Result;
 
 /// A trait for types that can be converted to `f64`.
 ///
@@ -79,6 +89,20 @@ macro_rules! impl_into_f64_lossless {
 
 impl_into_f64_lossless!(u8, i8, u16, i16, u32, i32, f32, f64);
 
+impl IntoF64 for Duration {
+    /// Converts the duration to seconds, from the integer number of
+    /// nanoseconds like [`Value::from_duration()`], rather than with the
+    /// lossy [`Duration::as_secs_f64()`].
+    ///
+    /// Scaling these seconds, as in `Value::new(duration)`, rounds once
+    /// more: the mantissa may then differ in its last bit from the one of
+    /// `Value::from_duration()`, which scales the nanoseconds directly.
+    #[inline]
+    fn into_f64(self) -> f64 {
+        self.as_nanos() as f64 / 1e9
+    }
+}
+
 #[cfg(feature = "lossy-conversions")]
 macro_rules! impl_into_f64_lossy {
     ($($t:ty),*) => {
@@ -96,16 +120,95 @@ macro_rules! impl_into_f64_lossy {
 #[cfg(feature = "lossy-conversions")]
 impl_into_f64_lossy!(u64, i64, usize, isize);
 
+/// A trait for the numeric types of the mantissa of a [`Value`], such as
+/// `f32` or `f64`.
+///
+/// External numeric types, such as decimals, implement it to be scaled in
+/// their own type with [`Value::new_typed()`]. By default, the prefix is
+/// selected from the `f64` approximation of the number, and the mantissa is
+/// scaled by `f64` powers of the base; implementations may override
+/// [`Mantissa::integral_exponent()`], [`Mantissa::div_pow()`] and
+/// [`Mantissa::mul_pow()`] to do both exactly.
+pub trait Mantissa: Copy + PartialEq + fmt::Debug + fmt::Display {
+    /// Converts self to `f64`, possibly approximately.
+    fn to_f64(self) -> f64;
+
+    /// Returns self divided by `divisor`, a power of the base such as
+    /// `1000.0` or `0.001`.
+    fn div_f64(self, divisor: f64) -> Self;
+
+    /// Returns self multiplied by `factor`, a power of the base.
+    fn mul_f64(self, factor: f64) -> Self;
+
+    /// Returns the exponent of the largest prefix of `base` not above self,
+    /// see [`Base::integral_exponent_for()`].
+    fn integral_exponent(self, base: &Base) -> i32 {
+        base.integral_exponent_for(self.to_f64())
+    }
+
+    /// Returns self divided by `base` raised to `exponent`.
+    fn div_pow(self, base: &Base, exponent: i32) -> Self {
+        self.div_f64(base.pow(exponent))
+    }
+
+    /// Returns self multiplied by `base` raised to `exponent`.
+    fn mul_pow(self, base: &Base, exponent: i32) -> Self {
+        self.mul_f64(base.pow(exponent))
+    }
+}
+
+impl Mantissa for f64 {
+    #[inline]
+    fn to_f64(self) -> f64 {
+        self
+    }
+
+    #[inline]
+    fn div_f64(self, divisor: f64) -> Self {
+        self / divisor
+    }
+
+    #[inline]
+    fn mul_f64(self, factor: f64) -> Self {
+        self * factor
+    }
+}
+
+impl Mantissa for f32 {
+    #[inline]
+    fn to_f64(self) -> f64 {
+        self.into()
+    }
+
+    #[inline]
+    fn div_f64(self, divisor: f64) -> Self {
+        self / divisor as f32
+    }
+
+    #[inline]
+    fn mul_f64(self, factor: f64) -> Self {
+        self * factor as f32
+    }
+}
+
 /// Defines the representation of the value.
-#[derive(Debug, PartialEq)]
-pub struct Value {
+///
+/// The mantissa is a `f64` by default, or any [`Mantissa`] type with
+/// [`Value::new_typed()`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Value<T = f64> {
     /// Mantissa of the value after scaling.
-    pub mantissa: f64,
+    pub mantissa: T,
 
-    /// Prefix indicating the scale.
+    /// Prefix indicating the scale, as a power of the base: `Kilo` is the
+    /// first step above the unit, which is `10³` in base 1000 but `1024` in
+    /// base 1024, a lakh (`10⁵`) in the Indian base and 万 (`10⁴`) in the
+    /// myriad bases. See [`Base::pow()`] for the scaling factor of a
+    /// prefix in a base.
     pub prefix: Prefix,
 
-    /// Indicates if the base is `1000` or `1024`.
+    /// Indicates the base, such as `1000`, `1024` or the Indian lakh-crore
+    /// scale.
     pub base: Base,
 }
 
@@ -153,6 +256,9 @@ impl Value {
 
     /// Returns a `Value` for the provided base.
     ///
+    /// If the constraint allows no prefix, the value is not scaled and its
+    /// prefix is `Prefix::Unit`.
+    ///
     /// # Example
     ///
     /// ```
@@ -177,13 +283,43 @@ impl Value {
     where
         F: IntoF64,
         C: AsRef<Constraint>,
+    {
+        Self::new_with_strategy(x, base, prefix_constraint, Floor)
+    }
+
+    /// Builds a `Value` like [`Value::new_with()`], but selects the prefix
+    /// with the provided `strategy`, see the [`strategy`][`crate::strategy`]
+    /// module.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use si_scale::prelude::{Base, Constraint, Prefix, Value};
+    /// use si_scale::strategy::Threshold;
+    ///
+    /// let actual = Value::new_with_strategy(
+    ///     950_000,
+    ///     Base::B1000,
+    ///     Constraint::None,
+    ///     Threshold(0.9),
+    /// );
+    /// let expected = Value {
+    ///     mantissa: 0.95f64,
+    ///     prefix: Prefix::Mega,
+    ///     base: Base::B1000,
+    /// };
+    /// assert_eq!(actual, expected);
+    /// ```
+    ///
+    pub fn new_with_strategy<F, C, S>(x: F, base: Base, prefix_constraint: C, strategy: S) -> Self
+    where
+        F: IntoF64,
+        C: AsRef<Constraint>,
+        S: Strategy,
     {
         let x: f64 = x.into_f64();
 
-        // Closest integral exponent (multiple of 3)
-        let exponent: i32 = base.integral_exponent_for(x);
-        // Clamp the exponent using the constraint on prefix
-        let prefix = Self::closest_prefix_for(exponent, prefix_constraint);
+        let prefix = strategy.select(x, &base, prefix_constraint.as_ref());
 
         let mantissa = x / base.pow(prefix.exponent());
 
@@ -194,6 +330,210 @@ impl Value {
         }
     }
 
+    /// Builds a `Value` like [`Value::new_with()`], for a number `x`
+    /// expressed in a unit raised to `power`, such as `2` for square
+    /// meters. Prefixes then scale the mantissa by the base raised to
+    /// `power`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use si_scale::prelude::{Base, Constraint, Prefix, Value};
+    ///
+    /// // 2.5e6 m² = 2.5 km²
+    /// let actual = Value::new_with_power(2.5e6, Base::B1000, Constraint::None, 2);
+    /// let expected = Value {
+    ///     mantissa: 2.5f64,
+    ///     prefix: Prefix::Kilo,
+    ///     base: Base::B1000,
+    /// };
+    /// assert_eq!(actual, expected);
+    /// assert_eq!(actual.to_f64_with_power(2), 2.5e6);
+    /// ```
+    ///
+    pub fn new_with_power<F, C>(x: F, base: Base, prefix_constraint: C, power: i32) -> Self
+    where
+        F: IntoF64,
+        C: AsRef<Constraint>,
+    {
+        let x: f64 = x.into_f64();
+
+        let exponent = base.integral_exponent_for_power(x, power);
+        let prefix = Self::closest_prefix_for(exponent, prefix_constraint);
+
+        let mantissa = x / base.pow_with_power(prefix.exponent(), power);
+
+        Value {
+            mantissa,
+            base,
+            prefix,
+        }
+    }
+
+    /// Builds a `Value` of `duration` in seconds, in base 1000, like
+    /// [`Value::new_with()`]. The mantissa is computed from the integer
+    /// number of nanoseconds rather than from [`Duration::as_secs_f64()`],
+    /// so that it is exact whenever it can be represented.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::time::Duration;
+    /// use si_scale::prelude::{Constraint, Prefix, Value};
+    ///
+    /// let actual = Value::from_duration(Duration::from_micros(1_234), Constraint::UnitAndBelow);
+    /// assert_eq!(actual.mantissa, 1.234);
+    /// assert_eq!(actual.prefix, Prefix::Milli);
+    /// ```
+    ///
+    pub fn from_duration<C>(duration: Duration, prefix_constraint: C) -> Self
+    where
+        C: AsRef<Constraint>,
+    {
+        let value = Value::new_with(duration, Base::B1000, prefix_constraint);
+
+        // Exponent of the prefix relative to the nanosecond.
+        let exponent = value.prefix.exponent() + 9;
+        let nanos = duration.as_nanos() as f64;
+        let mantissa = if exponent < 0 {
+            nanos * Base::B1000.pow(-exponent)
+        } else {
+            nanos / Base::B1000.pow(exponent)
+        };
+
+        Value { mantissa, ..value }
+    }
+
+    /// Splits `x` into at most `parts` values (at least one) with integral
+    /// mantissas and descending prefixes, such as `1 Gi`, `200 Mi` and
+    /// `12 ki` in base 1024.
+    ///
+    /// The first prefix is the one selected by [`Value::new_with()`], and the
+    /// following ones are the next lower prefixes allowed by the constraint.
+    /// Components equal to zero are omitted, and the remainder below the
+    /// last prefix is truncated, except for floating-point rounding errors:
+    /// `2.3` is `2` and `300 m`, not `299 m` and `999 µ`. A zero `x` returns a
+    /// single zero value.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use si_scale::prelude::{Base, Constraint, Prefix, Value};
+    ///
+    /// let x = (1 << 30) + 200 * (1 << 20) + 12 * (1 << 10) + 5;
+    /// let parts = Value::breakdown(x, Base::B1024, Constraint::UnitAndAbove, 3);
+    /// let mantissas: Vec<f64> = parts.iter().map(|value| value.mantissa).collect();
+    /// assert_eq!(mantissas, vec![1.0, 200.0, 12.0]);
+    /// assert_eq!(parts[2].prefix, Prefix::Kilo);
+    /// ```
+    ///
+    #[cfg(feature = "alloc")]
+    pub fn breakdown<F, C>(x: F, base: Base, prefix_constraint: C, parts: usize) -> Vec<Value>
+    where
+        F: IntoF64,
+        C: AsRef<Constraint>,
+    {
+        Self::breakdown_with_power(x.into_f64(), base, prefix_constraint.as_ref(), parts, 1)
+    }
+
+    /// Same as [`Value::breakdown()`], for a number `x` expressed in a unit
+    /// raised to `power`, see [`Value::new_with_power()`].
+    #[cfg(feature = "alloc")]
+    pub(crate) fn breakdown_with_power(
+        x: f64,
+        base: Base,
+        constraint: &Constraint,
+        parts: usize,
+        power: i32,
+    ) -> Vec<Value> {
+        let first = Value::new_with_power(x, base, constraint, power).prefix;
+        let sign = if x < 0.0 { -1.0 } else { 1.0 };
+
+        let mut prefixes = Vec::with_capacity(parts.max(1));
+        prefixes.push(first);
+        while prefixes.len() < parts {
+            let prefix = prefixes[prefixes.len() - 1];
+            let next = next_prefix(prefix, -1, constraint);
+            if next == prefix {
+                break;
+            }
+            prefixes.push(next);
+        }
+        let last = prefixes[prefixes.len() - 1];
+
+        // Scales `x` once to the last prefix, so that the components are
+        // split with exact operations on integral floats. Dividing by powers
+        // of the base is exact more often than multiplying by their inverse.
+        let factor = |exponent: i32| base.pow_with_power(exponent, power);
+        let scaled = if last.exponent() < 0 {
+            math::abs(x) * factor(-last.exponent())
+        } else {
+            math::abs(x) / factor(last.exponent())
+        };
+        // Rounding errors of `x` and of its scaling, as in `2.3` scaled to
+        // `2299999.9999999995 µ`, are rounded; the remainder is truncated.
+        let rounded = math::round(scaled);
+        let mut rest = if math::abs(scaled - rounded) <= scaled * 8.0 * f64::EPSILON {
+            rounded
+        } else {
+            math::floor(scaled)
+        };
+
+        let mut values = Vec::new();
+        for prefix in prefixes {
+            let ratio = match (prefix.exponent(), last.exponent()) {
+                (exponent, last) if last >= 0 => factor(exponent) / factor(last),
+                (exponent, last) if exponent <= 0 => factor(-last) / factor(-exponent),
+                (exponent, last) => factor(exponent) * factor(-last),
+            };
+            let mantissa = math::floor(rest / ratio);
+            rest -= mantissa * ratio;
+            if mantissa > 0.0 {
+                values.push(Value {
+                    mantissa: sign * mantissa,
+                    prefix,
+                    base,
+                });
+            }
+        }
+
+        if values.is_empty() {
+            values.push(Value {
+                mantissa: 0.0,
+                prefix: first,
+                base,
+            });
+        }
+        values
+    }
+
+    /// Same as [`Value::new_with()`], but returns an error instead of
+    /// falling back to the unit prefix if the constraint is invalid, see
+    /// [`Constraint::validate()`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use si_scale::prelude::{Base, Constraint, Prefix, PrefixSet, Value};
+    ///
+    /// let constraint = Constraint::Custom(vec![Prefix::Kilo, Prefix::Unit]);
+    /// let value = Value::try_new_with(1.5e3, Base::B1000, constraint).unwrap();
+    /// assert_eq!(value.prefix, Prefix::Kilo);
+    ///
+    /// let constraint = Constraint::Set(PrefixSet::EMPTY);
+    /// assert!(Value::try_new_with(1.5, Base::B1000, constraint).is_err());
+    /// ```
+    ///
+    #[cfg(feature = "alloc")]
+    pub fn try_new_with<F, C>(x: F, base: Base, prefix_constraint: C) -> Result<Self>
+    where
+        F: IntoF64,
+        C: AsRef<Constraint>,
+    {
+        prefix_constraint.as_ref().validate()?;
+        Ok(Value::new_with(x, base, prefix_constraint))
+    }
+
     /// Converts `self` to a `f64`.
     ///
     /// # Example
@@ -214,6 +554,12 @@ impl Value {
         self.mantissa * scale
     }
 
+    /// Converts `self` to a `f64`, for a value expressed in a unit raised
+    /// to `power`, see [`Value::new_with_power()`].
+    pub fn to_f64_with_power(&self, power: i32) -> f64 {
+        self.mantissa * self.base.pow_with_power(self.prefix.exponent(), power)
+    }
+
     /// Returns a number that represents the sign of self.
     ///
     /// - `1.0` if the number is positive, `+0.0` or `INFINITY`
@@ -233,52 +579,124 @@ impl Value {
     /// ```
     ///
     pub fn signum(&self) -> f64 {
-        self.mantissa.signum()
+        math::signum(self.mantissa)
     }
 
     /// Returns the closest prefix for the provided exponent, respecting the
     /// optional constraint.
     ///
-    /// # Panics
-    ///
-    /// - If the `Custom` allowed prefixes is an empty vector.
-    ///
-    fn closest_prefix_for<C: AsRef<Constraint>>(exponent: i32, constraint: C) -> Prefix {
-        use std::convert::TryFrom;
-
+    /// With a `Custom` or `Set` constraint, this is the largest allowed
+    /// prefix not above the exponent, or else the smallest allowed prefix,
+    /// whatever the order of the allowed prefixes. If no prefix is allowed,
+    /// this falls back to `Prefix::Unit`.
+    pub(crate) fn closest_prefix_for<C: AsRef<Constraint>>(exponent: i32, constraint: C) -> Prefix {
         match constraint.as_ref() {
             Constraint::None => {
-                Prefix::try_from(exponent.clamp(Prefix::Yocto as i32, Prefix::Yotta as i32))
+                Prefix::from_exponent(exponent.clamp(Prefix::Yocto as i32, Prefix::Yotta as i32))
                     .unwrap_or(Prefix::Unit)
             }
             Constraint::UnitOnly => Prefix::Unit,
             Constraint::UnitAndAbove => {
-                Prefix::try_from(exponent.clamp(Prefix::Unit as i32, Prefix::Yotta as i32))
+                Prefix::from_exponent(exponent.clamp(Prefix::Unit as i32, Prefix::Yotta as i32))
                     .unwrap_or(Prefix::Unit)
             }
             Constraint::UnitAndBelow => {
-                Prefix::try_from(exponent.clamp(Prefix::Yocto as i32, Prefix::Unit as i32))
+                Prefix::from_exponent(exponent.clamp(Prefix::Yocto as i32, Prefix::Unit as i32))
                     .unwrap_or(Prefix::Unit)
             }
+            #[cfg(feature = "alloc")]
             Constraint::Custom(allowed_prefixes) => {
-                if allowed_prefixes.is_empty() {
-                    panic!("At least one prefix should be allowed");
-                }
-                let smallest_prefix = *allowed_prefixes.first().unwrap();
-                if exponent < smallest_prefix as i32 {
-                    return smallest_prefix;
-                }
-                allowed_prefixes
-                    .iter()
-                    .take_while(|&&prefix| prefix as i32 <= exponent)
-                    .cloned()
-                    .last()
-                    .unwrap_or(Prefix::Unit)
+                closest_allowed_prefix(exponent, allowed_prefixes.iter().copied())
+            }
+            Constraint::Set(allowed_prefixes) => {
+                closest_allowed_prefix(exponent, allowed_prefixes.iter())
             }
         }
     }
 }
 
+/// Returns the largest of `allowed_prefixes` not above `exponent`, or else
+/// the smallest of them, or `Prefix::Unit` if there is none.
+fn closest_allowed_prefix<I>(exponent: i32, allowed_prefixes: I) -> Prefix
+where
+    I: Iterator<Item = Prefix>,
+{
+    let (below, smallest) = allowed_prefixes.fold((None, None), |(below, smallest), prefix| {
+        let below = match below {
+            _ if prefix as i32 > exponent => below,
+            Some(below) if below > prefix => Some(below),
+            _ => Some(prefix),
+        };
+        let smallest = match smallest {
+            Some(smallest) if smallest < prefix => Some(smallest),
+            _ => Some(prefix),
+        };
+        (below, smallest)
+    });
+    below.or(smallest).unwrap_or(Prefix::Unit)
+}
+
+impl<T: Mantissa> Value<T> {
+    /// Returns a `Value` of `x` for the provided base, like
+    /// [`Value::new_with()`], but with a mantissa computed in the numeric
+    /// type of `x`, such as `f32`, without going through `f64`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use si_scale::prelude::{Base, Constraint, Prefix, Value};
+    ///
+    /// let actual = Value::new_typed(0.1f32, Base::B1000, Constraint::None);
+    /// let expected = Value {
+    ///     mantissa: 100f32,
+    ///     prefix: Prefix::Milli,
+    ///     base: Base::B1000,
+    /// };
+    /// assert_eq!(actual, expected);
+    /// assert_eq!(format!("{}", actual), "100 m");
+    /// ```
+    ///
+    pub fn new_typed<C>(x: T, base: Base, prefix_constraint: C) -> Self
+    where
+        C: AsRef<Constraint>,
+    {
+        let exponent = x.integral_exponent(&base);
+        let prefix = Value::closest_prefix_for(exponent, prefix_constraint);
+        let mantissa = x.div_pow(&base, prefix.exponent());
+
+        Value {
+            mantissa,
+            prefix,
+            base,
+        }
+    }
+
+    /// Converts `self` back to its numeric type, see
+    /// [`Value::new_typed()`].
+    pub fn to_typed(&self) -> T {
+        self.mantissa.mul_pow(&self.base, self.prefix.exponent())
+    }
+
+    /// Writes self as with `"{}"` into `buf`, and returns the written
+    /// string, or `fmt::Error` if `buf` is too small. A buffer of
+    /// [`buffer::MAX_LEN`][`crate::buffer::MAX_LEN`] bytes is always large
+    /// enough for `f64` mantissas; see [`BufferFormat`] for more control.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use si_scale::prelude::Value;
+    ///
+    /// let value: Value = 1.3e-5.into();
+    /// let mut buf = [0u8; 8];
+    /// assert_eq!(value.write_to(&mut buf).unwrap(), "13 µ");
+    /// assert!(value.write_to(&mut buf[..4]).is_err());
+    /// ```
+    pub fn write_to<'b>(&self, buf: &'b mut [u8]) -> core::result::Result<&'b str, fmt::Error> {
+        BufferFormat::new(*self).write_to(buf)
+    }
+}
+
 //
 // From Value -> f64
 //
@@ -313,7 +731,7 @@ macro_rules! impl_from_num_for_value {
 // Display (simplistic)
 //
 
-impl fmt::Display for Value {
+impl<T: Mantissa> fmt::Display for Value<T> {
     /// A basic but limited way to display the value; it does not allow
     /// mantissa formatting. Consider using the
     /// [`format_value!()`][`crate::format_value`] macro instead.
@@ -332,9 +750,10 @@ impl fmt::Display for Value {
     /// ```
     ///
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match self.prefix {
-            Prefix::Unit => write!(f, "{}", self.mantissa),
-            _ => write!(f, "{} {}", self.mantissa, self.prefix),
+        // The step 0 of a custom scale may have a symbol, such as `s`.
+        match self.base.prefix_symbol(self.prefix) {
+            "" => write!(f, "{}", self.mantissa),
+            symbol => write!(f, "{}{}{}", self.mantissa, self.base.separator(), symbol),
         }
     }
 }
@@ -361,6 +780,137 @@ impl_from_num_for_value!(isize);
 mod tests {
     use super::*;
 
+    #[test]
+    fn typed_values() {
+        let actual = Value::new_typed(0.1f32, Base::B1000, Constraint::None);
+        assert_eq!(actual.mantissa, 100f32);
+        assert_eq!(actual.prefix, Prefix::Milli);
+        assert_eq!(actual.to_typed(), 0.1f32);
+
+        let actual = Value::new_typed(4.5e9f32, Base::B1024, Constraint::UnitAndAbove);
+        assert_eq!(format!("{:.2}", actual.mantissa), "4.19");
+        assert_eq!(actual.prefix, Prefix::Giga);
+
+        for x in [0.0, 1.3e-5, -4.3e-5, 950_000.0, 1.234e12] {
+            let typed = Value::new_typed(x, Base::B1000, Constraint::None);
+            assert_eq!(typed, Value::new_with(x, Base::B1000, Constraint::None));
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn value_breakdowns() {
+        let parts = |x: f64, base: Base, constraint: Constraint, count: usize| -> Vec<String> {
+            Value::breakdown(x, base, constraint, count)
+                .iter()
+                .map(|value| format!("{}", value))
+                .collect()
+        };
+
+        let x = 1_234_567_890.0;
+        assert_eq!(
+            parts(x, Base::B1000, Constraint::UnitAndAbove, 3),
+            vec!["1 G", "234 M", "567 k"]
+        );
+        assert_eq!(
+            parts(x, Base::B1000, Constraint::UnitAndAbove, 1),
+            vec!["1 G"]
+        );
+        assert_eq!(
+            parts(x, Base::B1000, Constraint::UnitAndAbove, 0),
+            vec!["1 G"]
+        );
+        assert_eq!(
+            parts(-x, Base::B1000, Constraint::UnitAndAbove, 2),
+            vec!["-1 G", "-234 M"]
+        );
+
+        let x = (3u64 << 30) as f64 + 5.0;
+        assert_eq!(
+            parts(x, Base::B1024, Constraint::UnitAndAbove, 4),
+            vec!["3 Gi", "5"]
+        );
+        assert_eq!(
+            parts(0.0, Base::B1024, Constraint::UnitAndAbove, 3),
+            vec!["0"]
+        );
+
+        let constraint = Constraint::custom(&[Prefix::Unit, Prefix::Mega]).unwrap();
+        assert_eq!(
+            parts(2_500_000.0, Base::B1000, constraint, 3),
+            vec!["2 M", "500000"]
+        );
+
+        let constraint = Constraint::UnitAndBelow;
+        assert_eq!(
+            parts(2.3, Base::B1000, constraint.clone(), 3),
+            vec!["2", "300 m"]
+        );
+        assert_eq!(
+            parts(1.001, Base::B1000, constraint.clone(), 3),
+            vec!["1", "1 m"]
+        );
+        assert_eq!(
+            parts(4.35, Base::B1000, constraint.clone(), 3),
+            vec!["4", "350 m"]
+        );
+        assert_eq!(
+            parts(-0.0123456, Base::B1000, constraint.clone(), 3),
+            vec!["-12 m", "-345 µ", "-600 n"]
+        );
+        assert_eq!(
+            parts(1.2345678, Base::B1000, constraint, 2),
+            vec!["1", "234 m"]
+        );
+
+        assert_eq!(
+            parts(1.5, Base::B1000, Constraint::UnitAndBelow, 3),
+            vec!["1", "500 m"]
+        );
+    }
+
+    #[test]
+    fn values_from_durations() {
+        use std::time::Duration;
+
+        let duration = Duration::new(1, 5);
+        let value = Value::from_duration(duration, Constraint::UnitAndBelow);
+        assert_eq!(value.mantissa, 1.000000005);
+        assert_eq!(value.prefix, Prefix::Unit);
+
+        let value = Value::from_duration(Duration::from_nanos(999), Constraint::None);
+        assert_eq!(value.mantissa, 999.0);
+        assert_eq!(value.prefix, Prefix::Nano);
+
+        let value = Value::from_duration(Duration::from_secs(2_000), Constraint::None);
+        assert_eq!(value.mantissa, 2.0);
+        assert_eq!(value.prefix, Prefix::Kilo);
+
+        assert_eq!(Duration::from_millis(250).into_f64(), 0.25);
+        // `as_secs_f64()` gives 1.0410001229999999.
+        assert_eq!(Duration::new(1, 41_000_123).into_f64(), 1.041000123);
+    }
+
+    #[test]
+    fn values_with_powers() {
+        let actual = Value::new_with_power(2.5e6, Base::B1000, Constraint::None, 2);
+        assert_eq!(actual.prefix, Prefix::Kilo);
+        assert_eq!(actual.mantissa, 2.5);
+        assert_eq!(actual.to_f64_with_power(2), 2.5e6);
+
+        // 3 cm³ = 3000 mm³
+        let actual = Value::new_with_power(3e-6, Base::B1000, Constraint::None, 3);
+        assert_eq!(actual.prefix, Prefix::Milli);
+        assert!((actual.mantissa - 3000.0).abs() < 1e-9);
+
+        let actual = Value::new_with_power(2.5e6, Base::B1000, Constraint::UnitOnly, 2);
+        assert_eq!(actual.prefix, Prefix::Unit);
+        assert_eq!(actual.mantissa, 2.5e6);
+
+        let actual = Value::new_with_power(1.5e3, Base::B1000, Constraint::None, 1);
+        assert_eq!(actual, Value::new(1.5e3));
+    }
+
     #[test]
     fn out_of_scale_values() {
         let actual = Value::new(1e-28);
@@ -711,6 +1261,67 @@ mod tests {
         assert_eq!(actual, expected);
     }
 
+    #[test]
+    fn values_with_other_bases() {
+        use crate::base::MyriadSymbols;
+
+        let actual = Value::new_with(1_234_567, Base::Indian, Constraint::None);
+        let expected = Value {
+            mantissa: 12.34567f64,
+            prefix: Prefix::Kilo,
+            base: Base::Indian,
+        };
+        assert_eq!(actual, expected);
+        assert_eq!(format!("{}", actual), "12.34567 L");
+
+        // Base 1024 writes the binary symbols, like `format_value!()`.
+        let actual = Value::new_with(1536, Base::B1024, Constraint::None);
+        assert_eq!(format!("{}", actual), "1.5 ki");
+
+        let base = Base::Myriad(MyriadSymbols::Traditional);
+        let actual = Value::new_with(3.4e8, base, Constraint::None);
+        let expected = Value {
+            mantissa: 3.4f64,
+            prefix: Prefix::Mega,
+            base,
+        };
+        assert_eq!(actual, expected);
+        assert_eq!(format!("{}", actual), "3.4億");
+    }
+
+    #[test]
+    fn values_with_custom_scales() {
+        use crate::base::Scale;
+
+        #[derive(Debug)]
+        struct Sexagesimal;
+
+        impl Scale for Sexagesimal {
+            fn ratio(&self) -> f64 {
+                60.0
+            }
+            fn symbol(&self, step: i32) -> &'static str {
+                match step {
+                    1 => "min",
+                    2 => "h",
+                    _ => "s",
+                }
+            }
+            fn max_step(&self) -> i32 {
+                2
+            }
+        }
+
+        static TIME: Sexagesimal = Sexagesimal;
+
+        let actual = Value::new_with(30, Base::Custom(&TIME), Constraint::None);
+        assert_eq!(actual.prefix, Prefix::Unit);
+        assert_eq!(format!("{}", actual), "30 s");
+
+        let actual = Value::new_with(5400, Base::Custom(&TIME), Constraint::None);
+        assert_eq!(format!("{}", actual), "1.5 h");
+    }
+
     #[test]
     fn values_with_prefix_constraints() {
         // For instance, seconds are never expressed as kilo-seconds, so
@@ -842,6 +1453,7 @@ mod tests {
     /// If the allowed prefixes are `Constraint::Custom(...)`, the function
     /// returns the corresponding prefix if the exponent matches one of them.
     #[test]
+    #[cfg(feature = "alloc")]
     fn closest_prefix_with_custom() {
         let constraint = Constraint::Custom(vec![Prefix::Milli, Prefix::Unit, Prefix::Kilo]);
 
@@ -881,12 +1493,131 @@ mod tests {
         assert_eq!(actual, expected);
     }
 
+    /// If the allowed prefixes are `Constraint::Set(...)`, the function
+    /// behaves as with `Constraint::Custom(...)`, whatever the order in
+    /// which the set was built.
+    #[test]
+    fn closest_prefix_with_set() {
+        use crate::prefix::PrefixSet;
+
+        let constraint = Constraint::Set(
+            PrefixSet::EMPTY
+                .with(Prefix::Kilo)
+                .with(Prefix::Milli)
+                .with(Prefix::Unit),
+        );
+
+        let exponent = -24;
+        let actual = Value::closest_prefix_for(exponent, &constraint);
+        let expected = Prefix::Milli;
+        assert_eq!(actual, expected);
+
+        let exponent = 0;
+        let actual = Value::closest_prefix_for(exponent, &constraint);
+        let expected = Prefix::Unit;
+        assert_eq!(actual, expected);
+
+        let exponent = 6;
+        let actual = Value::closest_prefix_for(exponent, &constraint);
+        let expected = Prefix::Kilo;
+        assert_eq!(actual, expected);
+
+        let exponent = -1; // should never happen
+        let actual = Value::closest_prefix_for(exponent, &constraint);
+        let expected = Prefix::Milli;
+        assert_eq!(actual, expected);
+    }
+
     #[test]
-    #[should_panic]
+    #[cfg(feature = "alloc")]
+    fn values_with_richer_constraints() {
+        let constraint = Constraint::exact(Prefix::Kilo);
+        let actual = Value::new_with(1.5e6, Base::B1000, &constraint);
+        let expected = Value {
+            mantissa: 1500f64,
+            prefix: Prefix::Kilo,
+            base: Base::B1000,
+        };
+        assert_eq!(actual, expected);
+
+        let constraint = Constraint::range(Prefix::Milli..=Prefix::Giga).unwrap();
+        let actual = Value::new_with(1.5e12, Base::B1000, &constraint);
+        let expected = Value {
+            mantissa: 1500f64,
+            prefix: Prefix::Giga,
+            base: Base::B1000,
+        };
+        assert_eq!(actual, expected);
+
+        let constraint = Constraint::excluding(&[Prefix::Micro]).unwrap();
+        let actual = Value::new_with(1.5e-5, Base::B1000, &constraint);
+        let expected = Value {
+            mantissa: 15000f64,
+            prefix: Prefix::Nano,
+            base: Base::B1000,
+        };
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn try_new_with_invalid_constraints() {
+        use crate::prefix::PrefixSet;
+        use crate::SIUnitsError;
+
+        let actual = Value::try_new_with(3, Base::B1000, Constraint::Custom(vec![]));
+        let expected = Err(SIUnitsError::InvalidConstraint(
+            "no prefix is allowed".to_string(),
+        ));
+        assert_eq!(actual, expected);
+
+        let actual = Value::try_new_with(3, Base::B1000, Constraint::Set(PrefixSet::EMPTY));
+        assert!(actual.is_err());
+
+        let constraint = Constraint::Custom(vec![Prefix::Mega, Prefix::Unit]);
+        let actual = Value::try_new_with(3e6, Base::B1000, &constraint);
+        assert_eq!(actual, Ok(Value::new_with(3e6, Base::B1000, &constraint)));
+        assert_eq!(actual.map(|value| value.prefix), Ok(Prefix::Mega));
+
+        let actual = Value::try_new_with(3e3, Base::B1000, Constraint::UnitAndAbove);
+        let expected = Ok(Value {
+            mantissa: 3f64,
+            prefix: Prefix::Kilo,
+            base: Base::B1000,
+        });
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn closest_prefix_with_set_empty() {
+        use crate::prefix::PrefixSet;
+
+        let constraint = Constraint::Set(PrefixSet::EMPTY);
+        let actual = Value::closest_prefix_for(3, constraint);
+        assert_eq!(actual, Prefix::Unit);
+
+        let constraint = Constraint::Set(PrefixSet::range(Prefix::Giga, Prefix::Milli));
+        let actual = Value::new_with(1.5e3, Base::B1000, constraint);
+        assert_eq!((actual.mantissa, actual.prefix), (1.5e3, Prefix::Unit));
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
     fn closest_prefix_with_custom_empty() {
         let constraint = Constraint::Custom(vec![]);
+        let actual = Value::closest_prefix_for(3, constraint);
+        assert_eq!(actual, Prefix::Unit);
+    }
 
-        let exponent = 3;
-        Value::closest_prefix_for(exponent, constraint);
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn closest_prefix_with_custom_unsorted() {
+        let constraint = Constraint::Custom(vec![Prefix::Kilo, Prefix::Unit]);
+        let actual = Value::new_with(5000, Base::B1000, &constraint);
+        assert_eq!((actual.mantissa, actual.prefix), (5.0, Prefix::Kilo));
+        let actual = Value::new_with(500, Base::B1000, &constraint);
+        assert_eq!((actual.mantissa, actual.prefix), (500.0, Prefix::Unit));
+        let actual = Value::new_with(0.5, Base::B1000, &constraint);
+        assert_eq!((actual.mantissa, actual.prefix), (0.5, Prefix::Unit));
     }
 }
@@ -0,0 +1,361 @@
+//! Spelled-out rendering of values, such as `13 microseconds` or
+//! `1 kibibyte`, for contexts where symbols read poorly, for instance with
+//! screen readers.
+//!
+//! The prefix long name (see
+//! [`Base::prefix_long_name()`][`crate::base::Base::prefix_long_name()`]) is
+//! prepended to the unit name, whose plural form is selected by a
+//! [`PluralRule`] applied to the mantissa as displayed.
+//!
+//! # Example
+//!
+//! ```
+//! use si_scale::prelude::{Base, Constraint, Value};
+//! use si_scale::words::{units, LongForm};
+//!
+//! let value = Value::new(1.3e-5);
+//! let actual = format!("{}", LongForm::new(&value, &units::SECOND));
+//! assert_eq!(actual, "13 microseconds");
+//!
+//! let value = Value::new_with(1024, Base::B1024, Constraint::UnitAndAbove);
+//! let actual = format!("{}", LongForm::new(&value, &units::BYTE));
+//! assert_eq!(actual, "1 kibibyte");
+//!
+//! // The plural form depends on the displayed mantissa: "1.0" is plural.
+//! let actual = format!("{:.1}", LongForm::new(&value, &units::BYTE));
+//! assert_eq!(actual, "1.0 kibibytes");
+//! ```
+
+use alloc::format;
+use core::fmt;
+
+use crate::base::Base;
+use crate::value::Value;
+
+/// Plural categories, as defined by the
+/// [Unicode CLDR](https://cldr.unicode.org/index/cldr-spec/plural-rules).
+///
+/// English only uses `One` and `Other`, but other languages need more
+/// categories.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PluralCategory {
+    /// Category used for zero in some languages (e.g. Arabic, Welsh).
+    Zero,
+    /// Singular form, e.g. "1 second".
+    One,
+    /// Dual form (e.g. Arabic, Slovenian).
+    Two,
+    /// Paucal form (e.g. Polish, Russian, Czech).
+    Few,
+    /// Form used for larger numbers in some languages (e.g. Polish, Russian).
+    Many,
+    /// General plural form, always available.
+    Other,
+}
+
+/// Selects the plural category for a number.
+///
+/// The number is provided exactly as it is going to be displayed, such as
+/// `"1"`, `"1.0"` or `"-2.5"`, because plural rules usually depend on the
+/// visible fraction digits.
+///
+/// Any closure `Fn(&str) -> PluralCategory` is a `PluralRule`, which makes it
+/// easy to plug the rules of other languages.
+pub trait PluralRule {
+    /// Returns the plural category of the displayed `number`.
+    fn category(&self, number: &str) -> PluralCategory;
+}
+
+impl<F> PluralRule for F
+where
+    F: Fn(&str) -> PluralCategory,
+{
+    fn category(&self, number: &str) -> PluralCategory {
+        self(number)
+    }
+}
+
+/// The English plural rule: `One` for `1` without visible decimals (and its
+/// negative counterpart), `Other` for everything else.
+///
+/// # Example
+///
+/// ```
+/// use si_scale::words::{English, PluralCategory, PluralRule};
+///
+/// assert_eq!(English.category("1"), PluralCategory::One);
+/// assert_eq!(English.category("1.0"), PluralCategory::Other);
+/// assert_eq!(English.category("0"), PluralCategory::Other);
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct English;
+
+impl PluralRule for English {
+    fn category(&self, number: &str) -> PluralCategory {
+        match number.trim_start_matches(['-', '+']) {
+            "1" => PluralCategory::One,
+            _ => PluralCategory::Other,
+        }
+    }
+}
+
+/// Holds the long names of a unit, one per plural category.
+///
+/// Only the `One` and `Other` forms are required; the forms of the other
+/// categories fall back to the `Other` form unless specified with
+/// [`UnitName::with()`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UnitName<'a> {
+    zero: Option<&'a str>,
+    one: &'a str,
+    two: Option<&'a str>,
+    few: Option<&'a str>,
+    many: Option<&'a str>,
+    other: &'a str,
+}
+
+impl<'a> UnitName<'a> {
+    /// Returns a unit name from its singular (`one`) and plural (`other`)
+    /// forms.
+    pub const fn new(one: &'a str, other: &'a str) -> Self {
+        UnitName {
+            zero: None,
+            one,
+            two: None,
+            few: None,
+            many: None,
+            other,
+        }
+    }
+
+    /// Returns self with the `form` used for the provided plural `category`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use si_scale::words::{PluralCategory, UnitName};
+    ///
+    /// // Polish
+    /// let sekunda = UnitName::new("sekunda", "sekundy")
+    ///     .with(PluralCategory::Few, "sekundy")
+    ///     .with(PluralCategory::Many, "sekund");
+    /// assert_eq!(sekunda.form(PluralCategory::Many), "sekund");
+    /// ```
+    pub const fn with(mut self, category: PluralCategory, form: &'a str) -> Self {
+        match category {
+            PluralCategory::Zero => self.zero = Some(form),
+            PluralCategory::One => self.one = form,
+            PluralCategory::Two => self.two = Some(form),
+            PluralCategory::Few => self.few = Some(form),
+            PluralCategory::Many => self.many = Some(form),
+            PluralCategory::Other => self.other = form,
+        }
+        self
+    }
+
+    /// Returns the form to use for the provided plural `category`.
+    pub fn form(&self, category: PluralCategory) -> &'a str {
+        let form = match category {
+            PluralCategory::Zero => self.zero,
+            PluralCategory::One => Some(self.one),
+            PluralCategory::Two => self.two,
+            PluralCategory::Few => self.few,
+            PluralCategory::Many => self.many,
+            PluralCategory::Other => None,
+        };
+        form.unwrap_or(self.other)
+    }
+}
+
+/// English names of common units.
+pub mod units {
+    use super::UnitName;
+
+    /// "second", "seconds"
+    pub const SECOND: UnitName<'static> = UnitName::new("second", "seconds");
+    /// "byte", "bytes"
+    pub const BYTE: UnitName<'static> = UnitName::new("byte", "bytes");
+    /// "bit", "bits"
+    pub const BIT: UnitName<'static> = UnitName::new("bit", "bits");
+    /// "meter", "meters"
+    pub const METER: UnitName<'static> = UnitName::new("meter", "meters");
+    /// "operation", "operations"
+    pub const OPERATION: UnitName<'static> = UnitName::new("operation", "operations");
+    /// "gram", "grams"
+    pub const GRAM: UnitName<'static> = UnitName::new("gram", "grams");
+    /// "tonne", "tonnes"
+    pub const TONNE: UnitName<'static> = UnitName::new("tonne", "tonnes");
+    /// "hertz", "hertz"
+    pub const HERTZ: UnitName<'static> = UnitName::new("hertz", "hertz");
+    /// "newton", "newtons"
+    pub const NEWTON: UnitName<'static> = UnitName::new("newton", "newtons");
+    /// "joule", "joules"
+    pub const JOULE: UnitName<'static> = UnitName::new("joule", "joules");
+    /// "watt", "watts"
+    pub const WATT: UnitName<'static> = UnitName::new("watt", "watts");
+}
+
+/// Displays a [`Value`] followed by the long names of its prefix and unit,
+/// such as `2.5 megabytes`.
+///
+/// The mantissa is displayed with `"{}"`, unless a precision is provided to
+/// the formatter, as in `"{:.2}"`. The plural rule defaults to [`English`],
+/// see [`LongForm::with_rule()`] to use another one.
+#[derive(Debug, Clone, Copy)]
+pub struct LongForm<'a, R = English> {
+    value: &'a Value,
+    unit: &'a UnitName<'a>,
+    rule: R,
+}
+
+impl<'a> LongForm<'a> {
+    /// Returns a `LongForm` using the English plural rule.
+    pub fn new(value: &'a Value, unit: &'a UnitName<'a>) -> Self {
+        LongForm {
+            value,
+            unit,
+            rule: English,
+        }
+    }
+}
+
+impl<'a, R> LongForm<'a, R> {
+    /// Returns self using the provided plural `rule` instead.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use si_scale::prelude::Value;
+    /// use si_scale::words::{LongForm, PluralCategory, UnitName};
+    ///
+    /// // French: 0 and 1 are singular, and so are 1.5 or 0.3.
+    /// let french = |n: &str| match n.trim_start_matches('-').parse::<f64>() {
+    ///     Ok(x) if x < 2.0 => PluralCategory::One,
+    ///     _ => PluralCategory::Other,
+    /// };
+    /// let seconde = UnitName::new("seconde", "secondes");
+    ///
+    /// let value = Value::new(1.5e-3);
+    /// let actual = format!("{}", LongForm::new(&value, &seconde).with_rule(french));
+    /// assert_eq!(actual, "1.5 milliseconde");
+    /// ```
+    pub fn with_rule<S: PluralRule>(self, rule: S) -> LongForm<'a, S> {
+        LongForm {
+            value: self.value,
+            unit: self.unit,
+            rule,
+        }
+    }
+}
+
+impl<R: PluralRule> fmt::Display for LongForm<'_, R> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let number = match f.precision() {
+            Some(precision) => format!("{:.*}", precision, self.value.mantissa),
+            None => format!("{}", self.value.mantissa),
+        };
+        let category = self.rule.category(&number);
+        let prefix = self.value.base.prefix_long_name(self.value.prefix);
+        let unit = self.unit.form(category);
+        match self.value.base {
+            // SI and binary prefixes form a single word with the unit, as in
+            // "2.5 megabytes".
+            Base::B1000 | Base::B1024 => write!(f, "{} {}{}", number, prefix, unit),
+            // Other scale words are separate words, as in "4.5 crore rupees"
+            // or "1.2万 seconds".
+            base => {
+                f.write_str(&number)?;
+                if !prefix.is_empty() {
+                    write!(f, "{}{}", base.separator(), prefix)?;
+                }
+                if !unit.is_empty() {
+                    write!(f, " {}", unit)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prefix::Constraint;
+
+    #[test]
+    fn english_plural_rule() {
+        assert_eq!(English.category("1"), PluralCategory::One);
+        assert_eq!(English.category("-1"), PluralCategory::One);
+        assert_eq!(English.category("1.0"), PluralCategory::Other);
+        assert_eq!(English.category("0"), PluralCategory::Other);
+        assert_eq!(English.category("2.5"), PluralCategory::Other);
+        assert_eq!(English.category("11"), PluralCategory::Other);
+    }
+
+    #[test]
+    fn unit_name_forms_fall_back_to_other() {
+        let name = UnitName::new("byte", "bytes");
+        assert_eq!(name.form(PluralCategory::One), "byte");
+        assert_eq!(name.form(PluralCategory::Other), "bytes");
+        assert_eq!(name.form(PluralCategory::Few), "bytes");
+
+        let name = name.with(PluralCategory::Few, "bytesy");
+        assert_eq!(name.form(PluralCategory::Few), "bytesy");
+        assert_eq!(name.form(PluralCategory::Many), "bytes");
+    }
+
+    #[test]
+    fn long_form() {
+        let value = Value::new(1.3e-5);
+        let actual = format!("{}", LongForm::new(&value, &units::SECOND));
+        assert_eq!(actual, "13 microseconds");
+
+        let value = Value::new(1);
+        let actual = format!("{}", LongForm::new(&value, &units::SECOND));
+        assert_eq!(actual, "1 second");
+
+        let value = Value::new_with(2.5e6, Base::B1000, Constraint::UnitAndAbove);
+        let actual = format!("{}", LongForm::new(&value, &units::BYTE));
+        assert_eq!(actual, "2.5 megabytes");
+
+        let value = Value::new_with(1024, Base::B1024, Constraint::UnitAndAbove);
+        let actual = format!("{}", LongForm::new(&value, &units::BYTE));
+        assert_eq!(actual, "1 kibibyte");
+
+        let value = Value::new(-1e3);
+        let actual = format!("{:.2}", LongForm::new(&value, &units::METER));
+        assert_eq!(actual, "-1.00 kilometers");
+
+        let rupee = UnitName::new("rupee", "rupees");
+        let value = Value::new_with(45_000_000, Base::Indian, Constraint::None);
+        let actual = format!("{}", LongForm::new(&value, &rupee));
+        assert_eq!(actual, "4.5 crore rupees");
+
+        let value = Value::new_with(5, Base::Indian, Constraint::None);
+        let actual = format!("{}", LongForm::new(&value, &rupee));
+        assert_eq!(actual, "5 rupees");
+    }
+
+    #[test]
+    fn long_form_myriad() {
+        use crate::base::MyriadSymbols;
+
+        let base = Base::Myriad(MyriadSymbols::Simplified);
+        let value = Value::new_with(5, base, Constraint::None);
+        let actual = format!("{}", LongForm::new(&value, &units::SECOND));
+        assert_eq!(actual, "5 seconds");
+
+        let value = Value::new_with(12_000, base, Constraint::None);
+        let actual = format!("{}", LongForm::new(&value, &units::SECOND));
+        assert_eq!(actual, "1.2万 seconds");
+    }
+
+    #[test]
+    fn long_form_with_rule() {
+        let value = Value::new(2);
+        let rule = |_: &str| PluralCategory::Two;
+        let name = UnitName::new("bit", "bits").with(PluralCategory::Two, "bitz");
+        let actual = format!("{}", LongForm::new(&value, &name).with_rule(rule));
+        assert_eq!(actual, "2 bitz");
+    }
+}